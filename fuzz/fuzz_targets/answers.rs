@@ -0,0 +1,9 @@
+#![no_main]
+
+use aoc::verify::Answers;
+use aoc::PuzzleInput;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = Answers::from_reader(data);
+});
@@ -0,0 +1,9 @@
+#![no_main]
+
+use aoc::params::Config;
+use aoc::PuzzleInput;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = Config::from_reader(data);
+});
@@ -0,0 +1,9 @@
+#![no_main]
+
+use aoc::day15::Day15;
+use aoc::{PuzzleInput, Solution};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = <Day15 as Solution>::Input::from_reader(data);
+});
@@ -0,0 +1,298 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{anyhow, Error, Result};
+
+use crate::error::summary;
+use crate::inputs;
+use crate::params::Config;
+use crate::pool;
+use crate::runner::{Day, Part, Solved};
+use crate::verify::Answers;
+use crate::PuzzleInput;
+
+/// Inputs of a whole team, saved as `<dir>/dayNN/<name>.txt`, along with the
+/// answers each member expects, optionally saved as `<dir>/answers/<name>.txt`
+/// in the format of [`Answers`].
+#[derive(Clone, Debug)]
+pub struct Batch {
+    inputs: Vec<(Day, String, PathBuf)>,
+    answers: BTreeMap<String, Answers>,
+}
+
+/// Outcome of one member's input of one day.
+#[derive(Debug)]
+pub struct Run {
+    pub day: u8,
+    pub name: String,
+    /// Parse time and a cell per part, or why the input failed as a whole.
+    pub result: Result<(Duration, Vec<Cell>)>,
+}
+
+#[derive(Debug)]
+pub enum Cell {
+    Answer {
+        value: String,
+        expected: Option<String>,
+        elapsed: Duration,
+    },
+    Failed(Error),
+    Unimplemented,
+}
+
+/// Runs grouped by day, one row per member and a column per part.
+pub struct Matrix<'a>(pub &'a [Run]);
+
+impl Batch {
+    /// Finds the inputs of `days` in `dir`. Days without a directory are
+    /// skipped, but finding no inputs at all is an error.
+    pub fn discover(dir: &Path, days: &[Day]) -> Result<Self> {
+        let inputs = days
+            .iter()
+            .map(|&day| {
+                let paths = texts(&dir.join(format!("day{:02}", day.day())))?;
+
+                Ok(paths.into_iter().map(move |(name, path)| (day, name, path)))
+            })
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+
+        if inputs.is_empty() {
+            return Err(anyhow!(
+                "No inputs in {}! Save them as dayNN/<name>.txt.",
+                dir.display()
+            ));
+        }
+
+        let answers = texts(&dir.join("answers"))?
+            .into_iter()
+            .map(|(name, path)| {
+                Answers::from_path(&path)
+                    .map(|answers| (name, answers))
+                    .map_err(|err| anyhow!("{}: {err}", path.display()))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self { inputs, answers })
+    }
+
+    /// Solves every part of every input on `jobs` worker threads. Failures,
+    /// panics included, only affect their own input.
+    pub fn run(&self, config: &Config, jobs: usize) -> Vec<Run> {
+        let results = pool::map(self.inputs.clone(), jobs, |(day, _, path)| {
+            inputs::open(&path)
+                .and_then(|reader| day.solve_timed(reader, &day.parts(), &config.params(&day)))
+        });
+
+        self.inputs
+            .iter()
+            .zip(results)
+            .map(|((day, name, _), result)| {
+                let result = result
+                    .unwrap_or_else(|panic| Err(anyhow!("Panicked: {panic}")))
+                    .map(|solved| self.cells(day.day(), name, solved));
+
+                Run {
+                    day: day.day(),
+                    name: name.clone(),
+                    result,
+                }
+            })
+            .collect()
+    }
+
+    fn cells(&self, day: u8, name: &str, mut solved: Solved) -> (Duration, Vec<Cell>) {
+        let cells = Part::ALL
+            .into_iter()
+            .map(
+                |part| match solved.answers.iter().position(|answer| answer.part == part) {
+                    Some(idx) => {
+                        let answer = solved.answers.remove(idx);
+
+                        match answer.value {
+                            Ok(value) => Cell::Answer {
+                                value,
+                                expected: self
+                                    .answers
+                                    .get(name)
+                                    .and_then(|answers| answers.get(day, part))
+                                    .map(str::to_owned),
+                                elapsed: answer.elapsed,
+                            },
+                            Err(err) => Cell::Failed(err),
+                        }
+                    }
+                    None => Cell::Unimplemented,
+                },
+            )
+            .collect();
+
+        (solved.parse, cells)
+    }
+}
+
+impl Run {
+    pub fn is_ok(&self) -> bool {
+        self.result
+            .as_ref()
+            .is_ok_and(|(_, cells)| cells.iter().all(Cell::is_ok))
+    }
+}
+
+impl Cell {
+    /// Whether the part was solved, with the expected answer if there is one.
+    pub fn is_ok(&self) -> bool {
+        match self {
+            Self::Answer {
+                value, expected, ..
+            } => expected
+                .as_ref()
+                .is_none_or(|expected| value.trim() == expected),
+            Self::Failed(_) => false,
+            Self::Unimplemented => true,
+        }
+    }
+}
+
+impl fmt::Display for Cell {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Answer {
+                value,
+                expected: Some(expected),
+                elapsed,
+            } if !self.is_ok() => write!(fmt, "{value} != {expected} ({elapsed:.2?})"),
+            Self::Answer { value, elapsed, .. } => write!(fmt, "{value} ({elapsed:.2?})"),
+            Self::Failed(err) => write!(fmt, "error: {}", summary(err)),
+            Self::Unimplemented => write!(fmt, "-"),
+        }
+    }
+}
+
+impl fmt::Display for Matrix<'_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0
+            .chunk_by(|a, b| a.day == b.day)
+            .try_for_each(|runs| {
+                let header = [
+                    format!("Day {}", runs[0].day),
+                    "Parse".to_owned(),
+                    "Part one".to_owned(),
+                    "Part two".to_owned(),
+                ];
+
+                let rows = runs
+                    .iter()
+                    .map(|run| {
+                        let mut row = vec![run.name.clone()];
+
+                        match &run.result {
+                            Ok((parse, cells)) => {
+                                row.push(format!("{parse:.2?}"));
+                                row.extend(cells.iter().map(Cell::to_string));
+                            }
+                            Err(err) => row.push(format!("error: {}", summary(err))),
+                        }
+
+                        row
+                    })
+                    .collect::<Vec<_>>();
+
+                let widths = header
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, title)| {
+                        // Errors of whole inputs run past the other columns.
+                        rows.iter()
+                            .filter(|row| row.len() == header.len() || idx + 1 < row.len())
+                            .filter_map(|row| row.get(idx))
+                            .chain([title])
+                            .map(|cell| cell.chars().count())
+                            .max()
+                            .unwrap_or_default()
+                    })
+                    .collect::<Vec<_>>();
+
+                [header.to_vec()].iter().chain(&rows).try_for_each(|row| {
+                    let line = row
+                        .iter()
+                        .zip(&widths)
+                        .map(|(cell, width)| format!("{cell:<width$}"))
+                        .collect::<Vec<_>>()
+                        .join("  ");
+
+                    writeln!(fmt, "{}", line.trim_end())
+                })?;
+
+                writeln!(fmt)
+            })?;
+
+        let failed = self.0.iter().filter(|run| !run.is_ok()).count();
+        write!(fmt, "Total: {} inputs, {failed} failed", self.0.len())
+    }
+}
+
+/// `.txt` files in `dir` by name, sorted. A missing directory has none.
+fn texts(dir: &Path) -> Result<Vec<(String, PathBuf)>> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(err) => return Err(anyhow!("{}: {err}", dir.display())),
+    };
+
+    let mut texts = entries
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<Vec<_>>>()
+        .map_err(|err| anyhow!("{}: {err}", dir.display()))?
+        .into_iter()
+        .filter(|path| path.extension().is_some_and(|ext| ext == "txt"))
+        .filter_map(|path| Some((path.file_stem()?.to_str()?.to_owned(), path)))
+        .collect::<Vec<_>>();
+
+    texts.sort();
+    Ok(texts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::DAYS;
+
+    #[test]
+    fn runs_every_input() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("aoc-batch-{}", std::process::id()));
+        let example = include_str!("../examples/day01-1.txt");
+
+        fs::create_dir_all(dir.join("day01"))?;
+        fs::create_dir_all(dir.join("answers"))?;
+        fs::write(dir.join("day01/alice.txt"), example)?;
+        fs::write(dir.join("day01/bob.txt"), example)?;
+        fs::write(dir.join("day01/carol.txt"), "1000\nabc\n")?;
+        fs::write(dir.join("answers/bob.txt"), "1 1 24000\n1 2 12345\n")?;
+
+        let batch = Batch::discover(&dir, &DAYS)?;
+        let runs = batch.run(&Config::default(), 2);
+        fs::remove_dir_all(&dir)?;
+
+        let names = runs
+            .iter()
+            .map(|run| (run.name.as_str(), run.is_ok()))
+            .collect::<Vec<_>>();
+        assert_eq!(names, [("alice", true), ("bob", false), ("carol", false)]);
+
+        let cells = runs[1].result.as_ref().map_err(|err| anyhow!("{err}"))?;
+        assert!(cells.1[1].to_string().starts_with("45000 != 12345 ("));
+
+        let matrix = Matrix(&runs).to_string();
+        assert!(matrix.starts_with("Day 1  "));
+        assert!(matrix.contains("carol  error: "));
+        assert!(matrix.ends_with("Total: 3 inputs, 2 failed"));
+        Ok(())
+    }
+}
@@ -0,0 +1,209 @@
+use std::fmt;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+
+use crate::params::Params;
+use crate::runner::{Day, Part};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+#[derive(Clone, Debug)]
+pub struct Bench {
+    pub day: u8,
+    pub runs: usize,
+    pub parse: Stats,
+    pub parts: Vec<(Part, Stats)>,
+}
+
+/// Summary table of several [`Bench`]es, one row per day and stage.
+pub struct Report<'a>(pub &'a [Bench]);
+
+impl Stats {
+    pub fn from_samples(mut samples: Vec<Duration>) -> Option<Self> {
+        samples.sort_unstable();
+
+        Some(Self {
+            min: *samples.first()?,
+            median: samples[samples.len() / 2],
+            max: *samples.last()?,
+        })
+    }
+}
+
+impl Bench {
+    /// Median time to parse the input and solve every part.
+    pub fn total(&self) -> Duration {
+        self.parse.median
+            + self
+                .parts
+                .iter()
+                .map(|(_, stats)| stats.median)
+                .sum::<Duration>()
+    }
+}
+
+impl fmt::Display for Report<'_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let row = |fmt: &mut fmt::Formatter<'_>, day: u8, stage: &str, stats: &Stats| {
+            writeln!(
+                fmt,
+                "{day:>3}  {stage:<8}  {:>10.2?}  {:>10.2?}  {:>10.2?}",
+                stats.min, stats.median, stats.max
+            )
+        };
+
+        writeln!(
+            fmt,
+            "Day  Stage     {:>10}  {:>10}  {:>10}",
+            "Min", "Median", "Max"
+        )?;
+
+        self.0.iter().try_for_each(|bench| {
+            row(fmt, bench.day, "parse", &bench.parse)?;
+            bench
+                .parts
+                .iter()
+                .try_for_each(|(part, stats)| row(fmt, bench.day, &format!("part {part}"), stats))
+        })?;
+
+        // The total of the medians, under their column.
+        write!(
+            fmt,
+            "Total{:>32.2?}",
+            self.0.iter().map(Bench::total).sum::<Duration>()
+        )
+    }
+}
+
+/// Parses and solves `parts` of `day` from `input` `runs` times, failing on the
+/// first error.
+pub fn bench(
+    day: &Day,
+    input: &str,
+    parts: &[Part],
+    params: &Params,
+    runs: usize,
+) -> Result<Bench> {
+    let mut parse = vec![];
+    let mut elapsed = vec![vec![]; parts.len()];
+
+    for _ in 0..runs {
+        let solved = day.solve_timed(input.as_bytes(), parts, params)?;
+
+        parse.push(solved.parse);
+        solved
+            .answers
+            .into_iter()
+            .zip(elapsed.iter_mut())
+            .try_for_each(|(answer, elapsed)| {
+                answer
+                    .value
+                    .map(|_| elapsed.push(answer.elapsed))
+                    .map_err(|err| anyhow!("Part {}: {err:#}", answer.part))
+            })?;
+    }
+
+    Ok(Bench {
+        day: day.day(),
+        runs,
+        parse: Stats::from_samples(parse).ok_or_else(|| anyhow!("No runs!"))?,
+        parts: parts
+            .iter()
+            .copied()
+            .zip(elapsed)
+            .flat_map(|(part, elapsed)| Some((part, Stats::from_samples(elapsed)?)))
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(samples: &[u64]) -> Option<Stats> {
+        Stats::from_samples(samples.iter().copied().map(Duration::from_millis).collect())
+    }
+
+    #[test]
+    fn summarizes_samples() {
+        let ms = Duration::from_millis;
+
+        assert_eq!(
+            stats(&[30, 10, 50, 20, 40]),
+            Some(Stats {
+                min: ms(10),
+                median: ms(30),
+                max: ms(50),
+            })
+        );
+        assert_eq!(
+            stats(&[40, 10, 30, 20]).map(|stats| stats.median),
+            Some(ms(30))
+        );
+        assert_eq!(
+            stats(&[7]),
+            Some(Stats {
+                min: ms(7),
+                median: ms(7),
+                max: ms(7),
+            })
+        );
+        assert_eq!(stats(&[]), None);
+    }
+
+    #[test]
+    fn runs_every_part_as_often_as_asked() -> Result<()> {
+        let day = Day::find(1)?;
+        let example = include_str!("../examples/day01-1.txt");
+
+        let bench = bench(&day, example, &day.parts(), &day.params(), 3)?;
+        assert_eq!((bench.day, bench.runs), (1, 3));
+        assert_eq!(
+            bench
+                .parts
+                .iter()
+                .map(|(part, _)| *part)
+                .collect::<Vec<_>>(),
+            Part::ALL
+        );
+        assert!(bench.total() >= bench.parse.median);
+
+        let err = super::bench(&day, example, &[Part::One], &day.params(), 0).err();
+        assert_eq!(err.map(|err| err.to_string()).as_deref(), Some("No runs!"));
+
+        let err = super::bench(&day, "\n", &[Part::One], &day.params(), 3).err();
+        assert_eq!(
+            err.map(|err| err.to_string()).as_deref(),
+            Some("Part one: No calories given!")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn reports_medians() {
+        let stats = stats(&[1, 2, 3]).unwrap_or_else(|| unreachable!());
+        let report = Report(&[Bench {
+            day: 7,
+            runs: 3,
+            parse: stats,
+            parts: vec![(Part::One, stats)],
+        }])
+        .to_string();
+
+        assert_eq!(
+            report.lines().collect::<Vec<_>>(),
+            [
+                "Day  Stage            Min      Median         Max",
+                "  7  parse         1.00ms      2.00ms      3.00ms",
+                "  7  part one      1.00ms      2.00ms      3.00ms",
+                "Total                          4.00ms",
+            ]
+        );
+    }
+}
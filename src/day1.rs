@@ -1,65 +1,94 @@
 use anyhow::{anyhow, Result};
-use std::{
-    collections::BinaryHeap,
-    io::{self, BufRead},
-};
-
-fn read_cals() -> Result<Vec<Vec<usize>>> {
-    let mut lines = io::stdin().lock().lines();
-    let mut elf_cals = vec![];
-    let mut cals = vec![];
-    let mut last_empty = false;
-
-    while let Some(Ok(line)) = lines.next() {
-        let empty = line.is_empty();
-
-        if empty {
-            if last_empty {
-                break;
-            } else {
-                elf_cals.push(cals);
-                cals = vec![];
-            }
-
-            last_empty = true;
-        } else {
-            cals.push(line.parse::<usize>()?);
-            last_empty = false;
-        }
+use std::{collections::BinaryHeap, io::BufRead};
+
+use crate::error::{number, ParseResult};
+use crate::params::{Param, Params};
+use crate::records::records;
+use crate::{PuzzleInput, Solution};
+
+pub struct Day1;
+
+impl PuzzleInput for Vec<Vec<usize>> {
+    fn from_reader(reader: impl BufRead) -> ParseResult<Self> {
+        records(reader)
+            .map(|record| record?.parse_lines(number))
+            .collect()
     }
+}
 
-    Ok(elf_cals)
+fn total(cals: impl IntoIterator<Item = usize>) -> Result<usize> {
+    cals.into_iter()
+        .try_fold(0usize, |sum, cal| sum.checked_add(cal))
+        .ok_or_else(|| anyhow!("Calories overflow!"))
 }
 
-fn part_one(food_cals: &[Vec<usize>]) -> Option<usize> {
+fn part_one(food_cals: &[Vec<usize>]) -> Result<Option<usize>> {
     food_cals
         .iter()
-        .map(|food_cal| food_cal.iter().sum::<usize>())
-        .max()
+        .map(|food_cal| total(food_cal.iter().copied()))
+        .try_fold(None, |max, cal| Ok(max.max(Some(cal?))))
 }
 
-fn part_two(food_cals: &[Vec<usize>]) -> Option<usize> {
-    const MAX_CALS_LEN: usize = 3;
-
+fn part_two(food_cals: &[Vec<usize>], max_cals_len: usize) -> Result<Option<usize>> {
     let mut cals = food_cals
         .iter()
-        .map(|food_cal| food_cal.iter().sum::<usize>())
-        .collect::<BinaryHeap<_>>();
+        .map(|food_cal| total(food_cal.iter().copied()))
+        .collect::<Result<BinaryHeap<_>>>()?;
 
-    (0..MAX_CALS_LEN).map(move |_| cals.pop()).sum()
+    (0..max_cals_len)
+        .map(move |_| cals.pop())
+        .collect::<Option<Vec<_>>>()
+        .map(total)
+        .transpose()
 }
 
-fn main() -> Result<()> {
-    let food_cals = read_cals()?;
+impl Solution for Day1 {
+    const DAY: u8 = 1;
 
-    println!(
-        "Part one: {}",
-        part_one(food_cals.as_slice()).ok_or_else(|| anyhow!("No calories given!"))?
-    );
-    println!(
-        "Part two: {}",
-        part_two(food_cals.as_slice()).ok_or_else(|| anyhow!("No calories given!"))?
-    );
+    type Input = Vec<Vec<usize>>;
+    type PartOne = usize;
+    type PartTwo = usize;
 
-    Ok(())
+    const PARAMS: &'static [Param] = &[("top", "3")];
+
+    fn part_one(food_cals: &Self::Input, _params: &Params) -> Result<usize> {
+        part_one(food_cals.as_slice())?.ok_or_else(|| anyhow!("No calories given!"))
+    }
+
+    fn part_two(food_cals: &Self::Input, params: &Params) -> Result<usize> {
+        part_two(food_cals.as_slice(), params.get("top")?)?
+            .ok_or_else(|| anyhow!("No calories given!"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../examples/day01-1.txt");
+
+    #[test]
+    fn part_one_example() -> Result<()> {
+        let input = <Day1 as Solution>::Input::from_text(EXAMPLE)?;
+        assert_eq!(Day1::part_one(&input, &Params::of::<Day1>())?, 24000);
+        Ok(())
+    }
+
+    #[test]
+    fn part_two_example() -> Result<()> {
+        let input = <Day1 as Solution>::Input::from_text(EXAMPLE)?;
+        assert_eq!(Day1::part_two(&input, &Params::of::<Day1>())?, 45000);
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_overflowing_calories() -> Result<()> {
+        let input = <Day1 as Solution>::Input::from_text(&format!("{}\n1\n", usize::MAX))?;
+        assert!(Day1::part_one(&input, &Params::of::<Day1>()).is_err());
+
+        let input = <Day1 as Solution>::Input::from_text(&format!("{}\n\n1\n\n1\n", usize::MAX))?;
+        assert_eq!(Day1::part_one(&input, &Params::of::<Day1>())?, usize::MAX);
+        assert!(Day1::part_two(&input, &Params::of::<Day1>()).is_err());
+        Ok(())
+    }
 }
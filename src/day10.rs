@@ -1,167 +1,331 @@
-use std::io;
+use std::{io::BufRead, str::FromStr};
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 
-use day10::{Cpu, Instr};
+use crate::error::{number, ParseError, ParseResult};
+use crate::params::Params;
+use crate::records::parse_lines;
+use crate::runner::Part;
+use crate::visual::Animation;
+use crate::{PuzzleInput, Solution};
 
-mod day10 {
-    use std::{
-        io::{BufRead, Stdin},
-        str::FromStr,
-    };
+pub struct Day10;
 
-    use anyhow::{anyhow, Error, Result};
+/// Letters the CRT can show, each four pixels wide and six tall, row by row.
+pub const FONT: [(char, &str); 17] = [
+    ('A', ".##.#..##..######..##..#"),
+    ('B', "###.#..####.#..##..####."),
+    ('C', ".##.#..##...#...#..#.##."),
+    ('E', "#####...###.#...#...####"),
+    ('F', "#####...###.#...#...#..."),
+    ('G', ".##.#..##...#.###..#.###"),
+    ('H', "#..##..######..##..##..#"),
+    ('I', ".###..#...#...#...#..###"),
+    ('J', "..##...#...#...##..#.##."),
+    ('K', "#..##.#.##..#.#.#.#.#..#"),
+    ('L', "#...#...#...#...#...####"),
+    ('O', ".##.#..##..##..##..#.##."),
+    ('P', "###.#..##..####.#...#..."),
+    ('R', "###.#..##..####.#.#.#..#"),
+    ('S', ".####...#....##....####."),
+    ('U', "#..##..##..##..##..#.##."),
+    ('Z', "####...#..#..#..#...####"),
+];
 
-    #[derive(Clone, Copy)]
-    pub enum Instr {
-        Noop,
-        AddX(isize),
-    }
+#[derive(Clone, Copy)]
+pub enum Instr {
+    Noop,
+    AddX(isize),
+}
 
-    pub struct Cpu {
-        clk: Clock,
-        crt: Crt,
-        x: isize,
+pub struct Cpu {
+    clk: Clock,
+    crt: Crt,
+    x: isize,
+}
+
+#[derive(Default)]
+struct Clock {
+    cycle: usize,
+}
+
+#[derive(Default)]
+struct Crt {
+    x: u8,
+    screen: String,
+}
+
+impl Default for Cpu {
+    fn default() -> Self {
+        Self {
+            clk: Default::default(),
+            crt: Default::default(),
+            x: 1,
+        }
     }
+}
+
+impl FromStr for Instr {
+    type Err = ParseError;
 
-    #[derive(Default)]
-    struct Clock {
-        cycle: usize,
+    fn from_str(str: &str) -> ParseResult<Self> {
+        let mut split = str.split_whitespace();
+
+        split
+            .next()
+            .ok_or_else(|| ParseError::missing("Missing Instr!"))
+            .map(|instr| match instr {
+                "noop" => Ok(Self::Noop),
+                "addx" => Ok(Self::AddX(
+                    split
+                        .next()
+                        .ok_or_else(|| ParseError::missing("Missing value!").with_token(str))
+                        .and_then(number)?,
+                )),
+                _ => Err(ParseError::invalid("Unknown Instr!", instr)),
+            })?
     }
+}
 
-    #[derive(Default)]
-    struct Crt {
-        x: u8,
+impl PuzzleInput for Vec<Instr> {
+    fn from_reader(reader: impl BufRead) -> ParseResult<Self> {
+        parse_lines(reader, str::parse)
     }
+}
 
-    impl Default for Cpu {
-        fn default() -> Self {
-            Self {
-                clk: Default::default(),
-                crt: Default::default(),
-                x: 1,
-            }
+impl Instr {
+    fn as_cycle_len(&self) -> usize {
+        match self {
+            Instr::Noop => 1,
+            Instr::AddX(_) => 2,
         }
     }
+}
 
-    impl FromStr for Instr {
-        type Err = Error;
-
-        fn from_str(str: &str) -> Result<Self> {
-            let mut split = str.split_whitespace();
-
-            split
-                .next()
-                .ok_or_else(|| anyhow!("Missing Instr!"))
-                .map(|instr| match instr {
-                    "noop" => Ok(Self::Noop),
-                    "addx" => Ok(Self::AddX(
-                        split
-                            .next()
-                            .ok_or_else(|| anyhow!("Missing value!"))?
-                            .parse()?,
-                    )),
-                    _ => Err(anyhow!("Unknown Instr!")),
-                })?
-        }
+impl Clock {
+    fn tick(&mut self) -> Option<usize> {
+        self.cycle += 1;
+        self.interesting_cycle()
     }
 
-    impl Instr {
-        pub fn from_stdin(stdin: Stdin) -> Result<Vec<Self>> {
-            stdin
-                .lock()
-                .lines()
-                .take_while(|line| {
-                    line.as_ref()
-                        .map(|line| line.is_empty())
-                        .map(|empty| !empty)
-                        .unwrap_or_default()
-                })
-                .flatten()
-                .map(|line| line.parse())
-                .collect()
-        }
+    fn interesting_cycle(&self) -> Option<usize> {
+        (self.cycle == 20 || (self.cycle > 20 && (self.cycle - 20).is_multiple_of(40)))
+            .then_some(self.cycle)
+    }
+}
 
-        fn as_cycle_len(&self) -> usize {
-            match self {
-                Instr::Noop => 1,
-                Instr::AddX(_) => 2,
-            }
-        }
+impl Cpu {
+    pub fn exec(&mut self, program: &[Instr]) -> Result<isize> {
+        self.exec_with(program, |_| ())
     }
 
-    impl Clock {
-        fn tick(&mut self) -> Option<usize> {
-            self.cycle += 1;
-            self.interesting_cycle()
-        }
+    /// Runs `program` like [`Cpu::exec`], calling `on_cycle` after every cycle.
+    pub fn exec_with(
+        &mut self,
+        program: &[Instr],
+        mut on_cycle: impl FnMut(&Self),
+    ) -> Result<isize> {
+        program
+            .iter()
+            .map(|&instr| self.handle_instr(instr, &mut on_cycle))
+            .try_fold(0isize, |sum, sig_strength| match sig_strength? {
+                Some(sig_strength) => sum
+                    .checked_add(sig_strength)
+                    .ok_or_else(|| anyhow!("Signal strength overflows!")),
+                None => Ok(sum),
+            })
+    }
 
-        fn interesting_cycle(&self) -> Option<usize> {
-            (self.cycle == 20 || (self.cycle > 20 && (self.cycle - 20) % 40 == 0))
-                .then_some(self.cycle)
-        }
+    /// Pixels drawn so far, one CRT row per line.
+    pub fn screen(&self) -> &str {
+        &self.crt.screen
     }
 
-    impl Cpu {
-        pub fn exec(&mut self, program: &[Instr]) -> isize {
-            program
-                .iter()
-                .flat_map(|&instr| self.handle_instr(instr))
-                .sum()
-        }
+    /// The screen as drawn so far, followed by the sprite position.
+    fn render(&self) -> String {
+        let mut frame = self
+            .crt
+            .screen
+            .lines()
+            .map(|line| format!("{line:<40}\n"))
+            .chain(std::iter::repeat_n(" ".repeat(40) + "\n", 6))
+            .take(6)
+            .collect::<String>();
 
-        fn handle_instr(&mut self, instr: Instr) -> Option<isize> {
-            let mut sig_strength = None;
+        frame += &(0..40)
+            .map(|x: isize| match x.abs_diff(self.x) <= 1 {
+                true => '#',
+                false => '-',
+            })
+            .collect::<String>();
+        frame += &format!("\nCycle {}, X = {}\n", self.clk.cycle, self.x);
+        frame
+    }
 
-            (0..instr.as_cycle_len()).for_each(|i| {
-                if let Some(cycle) = self.clk.tick() {
-                    sig_strength = Some(cycle as isize * self.x);
-                }
+    fn handle_instr(
+        &mut self,
+        instr: Instr,
+        on_cycle: &mut impl FnMut(&Self),
+    ) -> Result<Option<isize>> {
+        let mut sig_strength = None;
 
-                self.crt.draw(self.x);
+        (0..instr.as_cycle_len()).try_for_each(|i| -> Result<()> {
+            if let Some(cycle) = self.clk.tick() {
+                sig_strength = Some(
+                    (cycle as isize)
+                        .checked_mul(self.x)
+                        .ok_or_else(|| anyhow!("Signal strength overflows!"))?,
+                );
+            }
+
+            self.crt.draw(self.x);
 
-                match instr {
-                    Instr::AddX(val) if i == 1 => self.x += val,
-                    _ => (),
+            match instr {
+                Instr::AddX(val) if i == 1 => {
+                    self.x = self
+                        .x
+                        .checked_add(val)
+                        .ok_or_else(|| anyhow!("Register X overflows!"))?
                 }
-            });
+                _ => (),
+            }
 
-            sig_strength
-        }
+            on_cycle(self);
+            Ok(())
+        })?;
+
+        Ok(sig_strength)
     }
+}
 
-    impl Crt {
-        fn draw(&mut self, x: isize) {
-            let pixel = match [x - 1, x, x + 1]
-                .iter()
-                .map(|&x| x as u8)
-                .any(|x| x == self.x)
-            {
-                true => '#',
-                _ => '.',
-            };
+impl Crt {
+    fn draw(&mut self, x: isize) {
+        let pixel = match x.abs_diff(self.x as isize) <= 1 {
+            true => '#',
+            _ => '.',
+        };
 
-            print!("{pixel}");
+        self.screen.push(pixel);
 
-            self.x += 1;
-            self.x %= 40;
+        self.x += 1;
+        self.x %= 40;
 
-            if self.x == 0 {
-                println!();
-            }
+        if self.x == 0 {
+            self.screen.push('\n');
         }
     }
 }
 
-fn part_one(mut cpu: Cpu, program: &[Instr]) -> isize {
+fn part_one(mut cpu: Cpu, program: &[Instr]) -> Result<isize> {
     cpu.exec(program)
 }
 
-fn main() -> Result<()> {
-    let program = Instr::from_stdin(io::stdin())?;
+fn part_two(mut cpu: Cpu, program: &[Instr]) -> Result<Option<String>> {
+    cpu.exec(program)?;
+    Ok(read_letters(cpu.screen()))
+}
+
+/// Reads the eight letters off a full screen, each followed by a blank column.
+fn read_letters(screen: &str) -> Option<String> {
+    let rows = screen.lines().map(str::as_bytes).collect::<Vec<_>>();
+
+    if rows.len() != 6 || rows.iter().any(|row| row.len() != 40) {
+        return None;
+    }
+
+    (0..8)
+        .map(|idx| {
+            let glyph = rows
+                .iter()
+                .flat_map(|row| &row[idx * 5..idx * 5 + 4])
+                .map(|&pixel| pixel as char)
+                .collect::<String>();
+
+            FONT.iter()
+                .find(|(_, font)| *font == glyph)
+                .map(|&(letter, _)| letter)
+        })
+        .collect()
+}
+
+impl Solution for Day10 {
+    const DAY: u8 = 10;
+
+    type Input = Vec<Instr>;
+    type PartOne = isize;
+    type PartTwo = String;
+
+    fn part_one(program: &Self::Input, _params: &Params) -> Result<isize> {
+        part_one(Cpu::default(), program.as_slice())
+    }
+
+    /// The letters the CRT draws, which `aoc vis 10` shows as pixels.
+    fn part_two(program: &Self::Input, _params: &Params) -> Result<String> {
+        part_two(Cpu::default(), program.as_slice())?
+            .ok_or_else(|| anyhow!("CRT does not show eight letters! See it with aoc vis 10."))
+    }
+
+    /// Draws the CRT cycle by cycle. Both parts share the same picture.
+    fn visualize(
+        program: &Self::Input,
+        _params: &Params,
+        _part: Part,
+        animation: &mut Animation<'_>,
+    ) -> Result<()> {
+        let mut cpu = Cpu::default();
+        let mut drawn = Ok(());
+
+        cpu.exec_with(program, |cpu| {
+            if drawn.is_ok() {
+                drawn = animation.frame(|| cpu.render());
+            }
+        })?;
+
+        drawn?;
+        animation.finish(|| cpu.render())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    println!();
-    println!("Part one: {}", part_one(Cpu::default(), program.as_slice()));
+    const EXAMPLE: &str = include_str!("../examples/day10-1.txt");
 
-    Ok(())
+    #[test]
+    fn part_one_example() -> Result<()> {
+        let input = <Day10 as Solution>::Input::from_text(EXAMPLE)?;
+        assert_eq!(Day10::part_one(&input, &Params::of::<Day10>())?, 13140);
+        Ok(())
+    }
+
+    #[test]
+    fn part_two_example() -> Result<()> {
+        let input = <Day10 as Solution>::Input::from_text(EXAMPLE)?;
+        assert!(Day10::part_two(&input, &Params::of::<Day10>()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_overflowing_register() -> Result<()> {
+        let text = format!("addx {}\naddx 1\nnoop\n", isize::MAX);
+        let input = <Day10 as Solution>::Input::from_text(&text)?;
+        assert!(Day10::part_one(&input, &Params::of::<Day10>()).is_err());
+        assert!(Day10::part_two(&input, &Params::of::<Day10>()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn reads_letters() {
+        let screen = "###..#....####.####.#..#.#....###..###..\n\
+             #..#.#....#....#....#..#.#....#..#.#..#.\n\
+             #..#.#....###..###..#..#.#....#..#.###..\n\
+             ###..#....#....#....#..#.#....###..#..#.\n\
+             #....#....#....#....#..#.#....#....#..#.\n\
+             #....####.####.#.....##..####.#....###..\n";
+
+        assert_eq!(read_letters(screen).as_deref(), Some("PLEFULPB"));
+        assert_eq!(read_letters(&screen.replacen('#', ".", 1)), None);
+        assert_eq!(read_letters("#\n"), None);
+    }
 }
@@ -1,347 +1,454 @@
-use std::cell::RefCell;
-use std::collections::BinaryHeap;
-use std::io;
+use std::{cell::RefCell, collections::BinaryHeap, io::BufRead, str::FromStr};
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 
-use day11::Monkey;
+use crate::error::{number, ParseError, ParseResult};
+use crate::params::{Param, Params};
+use crate::records::{records, Record};
+use crate::trace::{self, Level};
+use crate::{PuzzleInput, Solution};
+
+pub struct Day11;
 
 const ACTIVE_MONKEY_LEN: u8 = 2;
 
-mod day11 {
-    use std::{
-        io::{BufRead, Stdin},
-        str::FromStr,
-    };
+#[derive(Debug, Clone)]
+enum Operation {
+    Add((Option<usize>, Option<usize>)),
+    Multiply((Option<usize>, Option<usize>)),
+}
 
-    use anyhow::{anyhow, Error, Result};
+#[derive(Debug, Clone)]
+struct Test {
+    condition: usize,
+    test_pass_result: usize,
+    test_fail_result: usize,
+}
 
-    #[derive(Debug, Clone)]
-    enum Operation {
-        Add((Option<usize>, Option<usize>)),
-        Multiply((Option<usize>, Option<usize>)),
-    }
+#[derive(Debug, Clone)]
+pub struct Monkey {
+    items: Vec<usize>,
+    operation: Operation,
+    test: Test,
+}
 
-    #[derive(Debug, Clone)]
-    struct Test {
-        condition: usize,
-        test_pass_result: usize,
-        test_fail_result: usize,
+impl FromStr for Operation {
+    type Err = ParseError;
+
+    fn from_str(str: &str) -> ParseResult<Self> {
+        let mut operation = str
+            .strip_prefix("Operation: new = ")
+            .ok_or_else(|| ParseError::invalid("Expected operation!", str))?
+            .split_whitespace();
+
+        let lhs = match operation
+            .next()
+            .ok_or_else(|| ParseError::missing("Missing LHS of operation!"))?
+        {
+            "old" => Ok(None),
+            lhs => usize::from_str(lhs)
+                .map(Some)
+                .map_err(|_| ParseError::invalid("Invalid LHS of operation!", lhs)),
+        }?;
+
+        let operator = operation
+            .next()
+            .ok_or_else(|| ParseError::missing("Missing operator of operation!"))?;
+
+        let rhs = match operation
+            .next()
+            .ok_or_else(|| ParseError::missing("Missing RHS of operation!"))?
+        {
+            "old" => Ok(None),
+            rhs => usize::from_str(rhs)
+                .map(Some)
+                .map_err(|_| ParseError::invalid("Invalid RHS of operation!", rhs)),
+        }?;
+
+        match operator {
+            "+" => Ok(Operation::Add((lhs, rhs))),
+            "*" => Ok(Operation::Multiply((lhs, rhs))),
+            _ => Err(ParseError::invalid(
+                "Invalid operator in expression!",
+                operator,
+            )),
+        }
     }
+}
 
-    #[derive(Debug, Clone)]
-    pub struct Monkey {
-        items: Vec<usize>,
-        operation: Operation,
-        test: Test,
+impl Operation {
+    fn evaluate(&self, old: usize) -> Option<usize> {
+        match *self {
+            Operation::Add((lhs, rhs)) => lhs.unwrap_or(old).checked_add(rhs.unwrap_or(old)),
+            Operation::Multiply((lhs, rhs)) => lhs.unwrap_or(old).checked_mul(rhs.unwrap_or(old)),
+        }
     }
+}
 
-    impl FromStr for Operation {
-        type Err = Error;
-
-        fn from_str(str: &str) -> Result<Self> {
-            let mut operation = str.split_at("Operation: new = ".len()).1.split_whitespace();
-
-            let lhs = match operation
-                .next()
-                .ok_or_else(|| anyhow!("Missing LHS of operation!"))?
-            {
-                "old" => Ok(None),
-                lhs => usize::from_str(lhs)
-                    .map(Some)
-                    .map_err(|_| anyhow!("Invalid LHS of operation!")),
-            }?;
-
-            let operator = operation
-                .next()
-                .map(|operator| operator.chars())
-                .and_then(|mut operator| operator.next())
-                .ok_or_else(|| anyhow!("Missing LHS of operation!"))?;
-
-            let rhs = match operation
-                .next()
-                .ok_or_else(|| anyhow!("Missing RHS of operation!"))?
-            {
-                "old" => Ok(None),
-                rhs => usize::from_str(rhs)
-                    .map(Some)
-                    .map_err(|_| anyhow!("Invalid RHS of operation!")),
-            }?;
-
-            match operator {
-                '+' => Ok(Operation::Add((lhs, rhs))),
-                '*' => Ok(Operation::Multiply((lhs, rhs))),
-                _ => Err(anyhow!("Invalid operator in expression!")),
-            }
-        }
+impl Test {
+    fn from_lines<'a>(lines: &mut impl Iterator<Item = (usize, &'a str)>) -> ParseResult<Self> {
+        let condition =
+            parse_line(
+                lines.next(),
+                "Missing condition in test!",
+                |line| match last_number(line)? {
+                    0 => Err(ParseError::invalid("Divisor must be positive!", "0")),
+                    condition => Ok(condition),
+                },
+            )?;
+        let test_pass_result =
+            parse_line(lines.next(), "Missing true-result in test!", last_number)?;
+        let test_fail_result =
+            parse_line(lines.next(), "Missing false-result in test!", last_number)?;
+
+        Ok(Test {
+            condition,
+            test_pass_result,
+            test_fail_result,
+        })
     }
 
-    impl Operation {
-        fn evaluate(&self, old: usize) -> usize {
-            match *self {
-                Operation::Add((lhs, rhs)) => lhs.unwrap_or(old) + rhs.unwrap_or(old),
-                Operation::Multiply((lhs, rhs)) => lhs.unwrap_or(old) * rhs.unwrap_or(old),
-            }
+    fn evaluate(&self, item: usize) -> usize {
+        if item.is_multiple_of(self.condition) {
+            self.test_pass_result
+        } else {
+            self.test_fail_result
         }
     }
+}
+
+impl PuzzleInput for Vec<Monkey> {
+    fn from_reader(reader: impl BufRead) -> ParseResult<Self> {
+        let records = records(reader).collect::<ParseResult<Vec<_>>>()?;
 
-    impl Test {
-        fn from_lines(lines: &[&str]) -> Result<Self> {
-            let mut lines = lines.iter();
-
-            let condition = lines
-                .next()
-                .map(|&condition| condition.split_whitespace())
-                .and_then(|condition| condition.last())
-                .map(usize::from_str)
-                .ok_or_else(|| anyhow!("Missing condition in test!"))??;
-
-            let test_pass_result = lines
-                .next()
-                .map(|&result| result.split_whitespace())
-                .and_then(|result| result.last())
-                .map(usize::from_str)
-                .ok_or_else(|| anyhow!("Missing true-result in test!"))??;
-
-            let test_fail_result = lines
-                .next()
-                .map(|&result| result.split_whitespace())
-                .and_then(|result| result.last())
-                .map(usize::from_str)
-                .ok_or_else(|| anyhow!("Missing false-result in test!"))??;
-
-            Ok(Test {
-                condition,
-                test_pass_result,
-                test_fail_result,
+        let monkeys = records
+            .iter()
+            .map(|record| {
+                Monkey::from_record(record)
+                    .map_err(|err| err.with_line(record.line, &record.lines[0]))
             })
-        }
+            .collect::<ParseResult<Vec<_>>>()?;
 
-        fn evaluate(&self, item: usize) -> usize {
-            if item % self.condition == 0 {
-                self.test_pass_result
-            } else {
-                self.test_fail_result
-            }
-        }
+        // Every throw must land on another monkey, as solving would panic
+        // otherwise.
+        records
+            .iter()
+            .zip(&monkeys)
+            .enumerate()
+            .try_for_each(|(idx, (record, monkey))| {
+                let targets = [monkey.test.test_pass_result, monkey.test.test_fail_result];
+
+                record
+                    .numbered()
+                    .skip(4)
+                    .zip(targets)
+                    .try_for_each(|((line, text), target)| {
+                        let err = match target {
+                            target if target >= monkeys.len() => "Throws to a missing monkey!",
+                            target if target == idx => "Monkey throws to itself!",
+                            _ => return Ok(()),
+                        };
+
+                        Err(ParseError::invalid(err, target.to_string()).with_line(line, text))
+                    })
+            })?;
+
+        Ok(monkeys)
     }
+}
 
-    impl Monkey {
-        pub fn from_stdin(stdin: Stdin) -> Result<Vec<Self>> {
-            const MONKEY_LINE_LEN: u8 = 6;
-
-            let mut last_valid = true;
-
-            stdin
-                .lock()
-                .lines()
-                .map(|line| {
-                    (
-                        line.as_ref()
-                            .map(|line| line.is_empty())
-                            .map(|empty| !empty)
-                            .unwrap_or_default(),
-                        line,
-                    )
-                })
-                .take_while(|(valid, _)| {
-                    let take = *valid || last_valid;
-                    last_valid = *valid;
-                    take
-                })
-                .map(|(_, line)| line.map_err(|err| anyhow!("{err}")))
-                .collect::<Result<Vec<_>>>()?
-                .into_iter()
-                .filter(|line| !line.is_empty())
-                .collect::<Vec<_>>()
-                .chunks(MONKEY_LINE_LEN as usize)
-                .map(Monkey::from_lines)
-                .collect()
-        }
-
-        pub fn test_quotient(&self) -> usize {
-            self.test.condition
-        }
+impl Monkey {
+    pub fn test_quotient(&self) -> usize {
+        self.test.condition
+    }
 
-        pub fn take_turn(
-            &mut self,
-            greatest_common_divisor: Option<usize>,
-            inspect_quotient: Option<usize>,
-        ) -> Vec<(usize, usize)> {
-            self.evaluate(greatest_common_divisor);
-            self.inspect(inspect_quotient);
-            self.throw()
-        }
+    pub fn take_turn(
+        &mut self,
+        greatest_common_divisor: Option<usize>,
+        inspect_quotient: Option<usize>,
+    ) -> Result<Vec<(usize, usize)>> {
+        self.evaluate(greatest_common_divisor)?;
+        self.inspect(inspect_quotient);
+        Ok(self.throw())
+    }
 
-        pub fn catch(&mut self, item: usize) {
-            self.items.push(item)
-        }
+    pub fn catch(&mut self, item: usize) {
+        self.items.push(item)
+    }
 
-        fn from_lines(lines: &[String]) -> Result<Self> {
-            let mut lines = lines.iter();
-
-            let id = lines
-                .next()
-                .map(|id| id.chars())
-                .and_then(|mut id| id.nth("Monkey ".len()))
-                .map(|id| id.to_digit(10))
-                .map(|id| id.ok_or_else(|| anyhow!("Invalid monkey ID!")))
-                .map(|id| id.map(|id| id as usize))
-                .ok_or_else(|| anyhow!("Missing id for monkey!"))??;
-
-            let items = lines
-                .next()
-                .map(|items| items.trim_start())
-                .map(|items| items.split_at("Starting items: ".len()))
-                .map(|items| items.1)
-                .map(|items| items.split(", "))
-                .map(|items| items.map(usize::from_str))
-                .map(|items| items.map(|item| item.map_err(|err| anyhow!(err))))
-                .map(|items| items.collect::<Result<_>>())
-                .ok_or_else(|| anyhow!("Missing starting items for monkey {id}!"))??;
-
-            let operation = lines
-                .next()
-                .map(|operation| operation.trim_start())
-                .map(Operation::from_str)
-                .ok_or_else(|| anyhow!("Missing operation for monkey {id}!"))??;
-
-            let test = [lines.next(), lines.next(), lines.next()]
-                .into_iter()
-                .map(|test| test.map(|test| test.trim_start()))
-                .collect::<Option<Vec<_>>>()
-                .as_deref()
-                .map(Test::from_lines)
-                .ok_or_else(|| anyhow!("Missing test for monkey {id}!"))??;
-
-            Ok(Self {
-                items,
-                operation,
-                test,
-            })
-        }
+    fn from_record(record: &Record) -> ParseResult<Self> {
+        let mut lines = record.numbered();
+
+        parse_line(lines.next(), "Missing id for monkey!", |id| {
+            id.strip_prefix("Monkey ")
+                .and_then(|id| id.strip_suffix(':'))
+                .ok_or_else(|| ParseError::invalid("Invalid monkey ID!", id))
+                .and_then(number::<usize>)
+        })?;
+
+        let items = parse_line(
+            lines.next(),
+            "Missing starting items for monkey!",
+            |items| {
+                items
+                    .strip_prefix("Starting items: ")
+                    .ok_or_else(|| ParseError::invalid("Expected starting items!", items))?
+                    .split(", ")
+                    .map(number)
+                    .collect()
+            },
+        )?;
+
+        let operation = parse_line(
+            lines.next(),
+            "Missing operation for monkey!",
+            Operation::from_str,
+        )?;
+
+        let test = Test::from_lines(&mut lines)?;
+
+        Ok(Self {
+            items,
+            operation,
+            test,
+        })
+    }
 
-        fn evaluate(&mut self, greatest_common_divisor: Option<usize>) {
-            self.items.iter_mut().for_each(|item| {
-                *item = self.operation.evaluate(
+    fn evaluate(&mut self, greatest_common_divisor: Option<usize>) -> Result<()> {
+        self.items.iter_mut().try_for_each(|item| {
+            *item = self
+                .operation
+                .evaluate(
                     greatest_common_divisor
                         .map(|divisor| *item % divisor)
                         .unwrap_or(*item),
                 )
-            });
-        }
+                .ok_or_else(|| anyhow!("Worry level overflowed!"))?;
 
-        fn inspect(&mut self, inspect_quotient: Option<usize>) {
-            inspect_quotient
-                .map(|quotient| self.items.iter_mut().for_each(|item| *item /= quotient))
-                .unwrap_or_default();
-        }
+            Ok(())
+        })
+    }
 
-        fn throw(&mut self) -> Vec<(usize, usize)> {
-            let thrown_items = self
-                .items
-                .iter()
-                .cloned()
-                .map(|item| (self.test.evaluate(item), item))
-                .collect();
+    fn inspect(&mut self, inspect_quotient: Option<usize>) {
+        inspect_quotient
+            .map(|quotient| self.items.iter_mut().for_each(|item| *item /= quotient))
+            .unwrap_or_default();
+    }
 
-            self.items = vec![];
+    fn throw(&mut self) -> Vec<(usize, usize)> {
+        let thrown_items = self
+            .items
+            .iter()
+            .cloned()
+            .map(|item| (self.test.evaluate(item), item))
+            .collect();
 
-            thrown_items
-        }
+        self.items = vec![];
+
+        thrown_items
     }
 }
 
-fn part_one(monkeys: Vec<RefCell<Monkey>>) -> usize {
-    const ROUND_LEN: u8 = 20;
-    const INSPECT_QUOTIENT: Option<u8> = Some(3);
+fn parse_line<T>(
+    line: Option<(usize, &str)>,
+    missing: &str,
+    parse: impl FnOnce(&str) -> ParseResult<T>,
+) -> ParseResult<T> {
+    let (idx, line) = line.ok_or_else(|| ParseError::missing(missing))?;
+    parse(line.trim_start()).map_err(|err| err.with_line(idx, line))
+}
+
+fn last_number(line: &str) -> ParseResult<usize> {
+    line.split_whitespace()
+        .last()
+        .ok_or_else(|| ParseError::missing("Missing number!"))
+        .and_then(number)
+}
 
+fn part_one(
+    monkeys: Vec<RefCell<Monkey>>,
+    round_len: usize,
+    inspect_quotient: usize,
+) -> Result<usize> {
     let mut inspection_lens = (0..monkeys.len()).map(|_| 0usize).collect::<Vec<_>>();
 
-    (0..ROUND_LEN).for_each(|_| {
-        monkeys.iter().enumerate().for_each(|(sender, monkey)| {
-            monkey
-                .borrow_mut()
-                .take_turn(None, INSPECT_QUOTIENT.map(|quotient| quotient as usize))
-                .into_iter()
-                .for_each(|(recipient, thrown_item)| {
-                    *inspection_lens
-                        .get_mut(sender)
-                        .unwrap_or_else(|| unreachable!()) += 1;
-
-                    monkeys
-                        .get(recipient)
-                        .unwrap_or_else(|| unreachable!())
-                        .borrow_mut()
-                        .catch(thrown_item);
-                })
-        });
-    });
-
-    inspection_lens
+    (0..round_len).try_for_each(|round| {
+        monkeys
+            .iter()
+            .enumerate()
+            .try_for_each(|(sender, monkey)| -> Result<()> {
+                monkey
+                    .borrow_mut()
+                    .take_turn(None, Some(inspect_quotient))?
+                    .into_iter()
+                    .for_each(|(recipient, thrown_item)| {
+                        trace::event(
+                            Level::Trace,
+                            "throw",
+                            &[
+                                ("round", &round),
+                                ("from", &sender),
+                                ("to", &recipient),
+                                ("worry", &thrown_item),
+                            ],
+                        );
+
+                        *inspection_lens
+                            .get_mut(sender)
+                            .unwrap_or_else(|| unreachable!()) += 1;
+
+                        monkeys
+                            .get(recipient)
+                            .unwrap_or_else(|| unreachable!())
+                            .borrow_mut()
+                            .catch(thrown_item);
+                    });
+
+                Ok(())
+            })
+    })?;
+
+    Ok(inspection_lens
         .into_iter()
         .collect::<BinaryHeap<_>>()
         .into_iter()
         .take(ACTIVE_MONKEY_LEN as usize)
-        .product()
+        .product())
 }
 
-fn part_two(monkeys: Vec<RefCell<Monkey>>) -> usize {
-    const ROUND_LEN: usize = 10_000;
+fn part_two(monkeys: Vec<RefCell<Monkey>>, round_len: usize) -> Result<usize> {
     const INSPECT_QUOTIENT: Option<u8> = None;
 
-    let greatest_common_divisor = Some(
-        monkeys
-            .iter()
-            .map(RefCell::borrow)
-            .map(|monkey| monkey.test_quotient())
-            .product(),
-    );
+    let greatest_common_divisor = monkeys
+        .iter()
+        .map(RefCell::borrow)
+        .map(|monkey| monkey.test_quotient())
+        .try_fold(1usize, usize::checked_mul)
+        .ok_or_else(|| anyhow!("Product of divisors overflowed!"))?;
+    let greatest_common_divisor = Some(greatest_common_divisor);
 
     let mut inspection_lens = (0..monkeys.len()).map(|_| 0usize).collect::<Vec<_>>();
 
-    (0..ROUND_LEN).for_each(|_| {
-        monkeys.iter().enumerate().for_each(|(sender, monkey)| {
-            monkey
-                .borrow_mut()
-                .take_turn(
-                    greatest_common_divisor,
-                    INSPECT_QUOTIENT.map(|quotient| quotient as usize),
-                )
-                .into_iter()
-                .for_each(|(recipient, thrown_item)| {
-                    *inspection_lens
-                        .get_mut(sender)
-                        .unwrap_or_else(|| unreachable!()) += 1;
-
-                    monkeys
-                        .get(recipient)
-                        .unwrap_or_else(|| unreachable!())
-                        .borrow_mut()
-                        .catch(thrown_item);
-                })
-        });
-    });
-
-    inspection_lens
+    (0..round_len).try_for_each(|round| {
+        monkeys
+            .iter()
+            .enumerate()
+            .try_for_each(|(sender, monkey)| -> Result<()> {
+                monkey
+                    .borrow_mut()
+                    .take_turn(
+                        greatest_common_divisor,
+                        INSPECT_QUOTIENT.map(|quotient| quotient as usize),
+                    )?
+                    .into_iter()
+                    .for_each(|(recipient, thrown_item)| {
+                        trace::event(
+                            Level::Trace,
+                            "throw",
+                            &[
+                                ("round", &round),
+                                ("from", &sender),
+                                ("to", &recipient),
+                                ("worry", &thrown_item),
+                            ],
+                        );
+
+                        *inspection_lens
+                            .get_mut(sender)
+                            .unwrap_or_else(|| unreachable!()) += 1;
+
+                        monkeys
+                            .get(recipient)
+                            .unwrap_or_else(|| unreachable!())
+                            .borrow_mut()
+                            .catch(thrown_item);
+                    });
+
+                Ok(())
+            })
+    })?;
+
+    Ok(inspection_lens
         .into_iter()
         .collect::<BinaryHeap<_>>()
         .into_iter()
         .take(ACTIVE_MONKEY_LEN as usize)
-        .product()
+        .product())
+}
+
+impl Solution for Day11 {
+    const DAY: u8 = 11;
+
+    type Input = Vec<Monkey>;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    const PARAMS: &'static [Param] = &[
+        ("rounds_one", "20"),
+        ("relief", "3"),
+        ("rounds_two", "10000"),
+    ];
+
+    fn part_one(monkeys: &Self::Input, params: &Params) -> Result<usize> {
+        let relief = match params.get("relief")? {
+            0 => return Err(anyhow!("Relief must be positive!")),
+            relief => relief,
+        };
+
+        part_one(
+            monkeys.iter().cloned().map(RefCell::new).collect(),
+            params.get("rounds_one")?,
+            relief,
+        )
+    }
+
+    fn part_two(monkeys: &Self::Input, params: &Params) -> Result<usize> {
+        part_two(
+            monkeys.iter().cloned().map(RefCell::new).collect(),
+            params.get("rounds_two")?,
+        )
+    }
 }
 
-fn main() -> Result<()> {
-    let monkeys = Monkey::from_stdin(io::stdin())?;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    println!(
-        "Part one: {}",
-        part_one(monkeys.iter().cloned().map(RefCell::new).collect())
-    );
-    println!(
-        "Part two: {}",
-        part_two(monkeys.iter().cloned().map(RefCell::new).collect())
-    );
+    const EXAMPLE: &str = include_str!("../examples/day11-1.txt");
+
+    #[test]
+    fn part_one_example() -> Result<()> {
+        let input = <Day11 as Solution>::Input::from_text(EXAMPLE)?;
+        assert_eq!(Day11::part_one(&input, &Params::of::<Day11>())?, 10605);
+        Ok(())
+    }
 
-    Ok(())
+    #[test]
+    fn part_two_example() -> Result<()> {
+        let input = <Day11 as Solution>::Input::from_text(EXAMPLE)?;
+        assert_eq!(Day11::part_two(&input, &Params::of::<Day11>())?, 2713310158);
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_throws_that_cannot_land() {
+        let error = |input: &str| {
+            <Day11 as Solution>::Input::from_text(input)
+                .err()
+                .map(|err| (err.message().to_owned(), err.line()))
+        };
+
+        assert_eq!(
+            error(&EXAMPLE.replace("throw to monkey 3", "throw to monkey 4")),
+            Some(("Throws to a missing monkey!".to_owned(), Some(6)))
+        );
+        assert_eq!(
+            error(&EXAMPLE.replace("throw to monkey 2", "throw to monkey 0")),
+            Some(("Monkey throws to itself!".to_owned(), Some(5)))
+        );
+        assert_eq!(
+            error(&EXAMPLE.replace("divisible by 23", "divisible by 0")),
+            Some(("Divisor must be positive!".to_owned(), Some(4)))
+        );
+    }
 }
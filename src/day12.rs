@@ -1,185 +1,197 @@
-use std::io;
-
-use anyhow::Result;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::io::BufRead;
+
+use anyhow::{anyhow, Result};
+
+use crate::error::{ParseError, ParseResult};
+use crate::geometry::{BoundingBox, Point};
+use crate::grid::Grid;
+use crate::params::Params;
+use crate::runner::Part;
+use crate::trace::{self, Level};
+use crate::visual::{self, Animation};
+use crate::{PuzzleInput, Solution};
+
+pub struct Day12;
+
+pub struct HeatMap {
+    grid: Grid<char>,
+    end: Point,
+}
 
-use day12::HeatMap;
+impl fmt::Debug for HeatMap {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "{}", self.grid)
+    }
+}
 
-mod day12 {
-    use std::collections::{HashMap, HashSet, VecDeque};
-    use std::fmt;
-    use std::io::{BufRead, Stdin};
+impl PuzzleInput for HeatMap {
+    fn from_reader(reader: impl BufRead) -> ParseResult<Self> {
+        let grid = Grid::from_reader(reader, |elevation| match elevation {
+            'a'..='z' | 'S' | 'E' => Ok(elevation),
+            _ => Err(ParseError::invalid("Invalid elevation!", elevation)),
+        })?;
 
-    use anyhow::{anyhow, Result};
+        let end = grid
+            .position(|&elevation| elevation == 'E')
+            .ok_or_else(|| ParseError::missing("Missing end position!"))?;
 
-    pub struct HeatMap {
-        grid: Vec<Vec<char>>,
-        len: usize,
-        end: (usize, usize),
+        Ok(Self { grid, end })
     }
+}
 
-    impl fmt::Debug for HeatMap {
-        fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
-            self.grid
-                .iter()
-                .try_for_each(|row| writeln!(fmt, "{}", row.iter().collect::<String>()))
-        }
+impl HeatMap {
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        self.grid.points()
     }
 
-    impl HeatMap {
-        pub fn from_stdin(stdin: Stdin) -> Result<Self> {
-            let grid = stdin
-                .lock()
-                .lines()
-                .take_while(|line| {
-                    line.as_ref()
-                        .map(|line| line.is_empty())
-                        .map(|empty| !empty)
-                        .unwrap_or_default()
-                })
-                .map(|line| line.map(|line| line.chars().collect()))
-                .map(|line| line.map_err(|err| anyhow!(err)))
-                .collect::<Result<Vec<Vec<_>>>>()?;
-
-            let len = grid
-                .first()
-                .map(|row| row.len())
-                .ok_or_else(|| anyhow!("Empty grid!"))?;
-
-            let end = (0..len)
-                .flat_map(|i| (0..len).map(move |j| (i, j)))
-                .find(|&(i, j)| {
-                    grid.get(i)
-                        .and_then(|row| row.get(j))
-                        .map(|&elevation| elevation == 'E')
-                        .unwrap_or_default()
-                })
-                .ok_or_else(|| anyhow!("Missing end position!"))?;
+    pub fn get(&self, point: Point) -> Option<char> {
+        self.grid.get(point).copied()
+    }
 
-            Ok(Self { grid, len, end })
-        }
+    pub fn find_path(&self, start: Point) -> Option<VecDeque<Point>> {
+        self.find_path_with(start, |_, _| ())
+    }
 
-        pub fn len(&self) -> usize {
-            self.len
-        }
+    /// Finds a path like [`HeatMap::find_path`], calling `on_step` with the
+    /// open set and every point reached so far after each expanded point.
+    pub fn find_path_with(
+        &self,
+        start: Point,
+        mut on_step: impl FnMut(&HashSet<Point>, &HashMap<Point, usize>),
+    ) -> Option<VecDeque<Point>> {
+        // TODO(milesdiprata): Use priority-queue
+        let mut open_set = HashSet::new();
+        let mut came_from = HashMap::new();
 
-        pub fn get(&self, (i, j): (usize, usize)) -> Option<char> {
-            self.grid.get(i).and_then(|row| row.get(j)).copied()
-        }
+        let mut g_scores = HashMap::new();
+        let mut f_scores = HashMap::new();
 
-        pub fn find_path(&self, start: (usize, usize)) -> Option<VecDeque<(usize, usize)>> {
-            // TODO(milesdiprata): Use priority-queue
-            let mut open_set = HashSet::new();
-            let mut came_from = HashMap::new();
+        open_set.insert(start);
 
-            let mut g_scores = HashMap::new();
-            let mut f_scores = HashMap::new();
+        g_scores.insert(start, 0);
+        f_scores.insert(start, self.h_score(start));
 
-            open_set.insert(start);
+        while !open_set.is_empty() {
+            let current = open_set
+                .iter()
+                .find(|&point| {
+                    f_scores.get(point)
+                        == open_set.iter().flat_map(|point| f_scores.get(point)).min()
+                })
+                .copied()
+                .unwrap_or_else(|| unreachable!("Open-set cannot be empty"));
 
-            g_scores.insert(start, 0);
-            f_scores.insert(start, self.h_score(start));
+            if current == self.end {
+                return Self::reconstruct_path(&came_from, current);
+            }
 
-            while !open_set.is_empty() {
-                let current = open_set
-                    .iter()
-                    .find(|&coord| {
-                        f_scores.get(coord)
-                            == open_set.iter().flat_map(|coord| f_scores.get(coord)).min()
-                    })
-                    .copied()
-                    .unwrap_or_else(|| unreachable!("Open-set cannot be empty"));
+            open_set.remove(&current);
 
-                if current == self.end {
-                    return Self::reconstruct_path(&came_from, current);
-                }
+            let g_score = g_scores.get(&current).copied().unwrap_or(usize::MAX);
 
-                open_set.remove(&current);
+            trace::event(
+                Level::Trace,
+                "expand",
+                &[("at", &current), ("g", &g_score), ("open", &open_set.len())],
+            );
 
-                self.neighbors(current).into_iter().for_each(|neighbor| {
-                    let tentative_g_score =
-                        g_scores.get(&current).copied().unwrap_or(usize::MAX) + 1;
+            self.neighbors(current).into_iter().for_each(|neighbor| {
+                let tentative_g_score = g_score + 1;
 
-                    if tentative_g_score < g_scores.get(&neighbor).copied().unwrap_or(usize::MAX) {
-                        if !open_set.contains(&neighbor) {
-                            open_set.insert(neighbor);
-                        }
+                if tentative_g_score < g_scores.get(&neighbor).copied().unwrap_or(usize::MAX) {
+                    if !open_set.contains(&neighbor) {
+                        open_set.insert(neighbor);
+                    }
 
-                        came_from.insert(neighbor, current);
+                    came_from.insert(neighbor, current);
 
-                        g_scores.insert(neighbor, tentative_g_score);
-                        f_scores.insert(neighbor, tentative_g_score + self.h_score(neighbor));
-                    }
-                });
-            }
+                    g_scores.insert(neighbor, tentative_g_score);
+                    f_scores.insert(neighbor, tentative_g_score + self.h_score(neighbor));
+                }
+            });
 
-            None
+            on_step(&open_set, &g_scores);
         }
 
-        fn get_elevation(&self, coord: (usize, usize)) -> Option<char> {
-            self.get(coord).map(|elevation| match elevation {
-                'S' => 'a',
-                'E' => 'z',
-                elevation => elevation,
-            })
-        }
+        None
+    }
+
+    /// Draws the open set as `+`, points already expanded as `.` and `path` as
+    /// `#`, over the elevations.
+    fn render(
+        &self,
+        open_set: &HashSet<Point>,
+        reached: &HashMap<Point, usize>,
+        path: &VecDeque<Point>,
+    ) -> String {
+        let bounds = BoundingBox::from_points(self.points()).unwrap_or(BoundingBox::new(self.end));
+
+        visual::render(&bounds, |point| match self.get(point).unwrap_or(' ') {
+            elevation @ ('S' | 'E') => elevation,
+            _ if path.contains(&point) => '#',
+            _ if open_set.contains(&point) => '+',
+            _ if reached.contains_key(&point) => '.',
+            elevation => elevation,
+        })
+    }
+
+    fn get_elevation(&self, point: Point) -> Option<char> {
+        self.get(point).map(|elevation| match elevation {
+            'S' => 'a',
+            'E' => 'z',
+            elevation => elevation,
+        })
+    }
 
-        fn neighbors(&self, (i, j): (usize, usize)) -> Vec<(usize, usize)> {
-            [
-                (i as isize - 1, j as isize),
-                (i as isize + 1, j as isize),
-                (i as isize, j as isize - 1),
-                (i as isize, j as isize + 1),
-            ]
-            .into_iter()
-            .filter(|&(i, _)| i >= 0)
-            .filter(|&(_, j)| j >= 0)
-            .map(|(i, j)| (i as usize, j as usize))
-            .filter(|&neighbor| self.get(neighbor).is_some())
+    fn neighbors(&self, point: Point) -> Vec<Point> {
+        self.grid
+            .neighbors(point)
             .filter(|&neighbor| {
                 self.get_elevation(neighbor)
                     .map(|elevation| elevation as i8)
                     .unwrap_or_default()
                     - self
-                        .get_elevation((i, j))
+                        .get_elevation(point)
                         .map(|elevation| elevation as i8)
                         .unwrap_or_default()
                     <= 1
             })
             .collect()
-        }
-
-        fn h_score(&self, coord: (usize, usize)) -> usize {
-            Self::euclidean_distance(coord, self.end)
-        }
+    }
 
-        fn euclidean_distance(p: (usize, usize), q: (usize, usize)) -> usize {
-            ((q.0 as f64 - p.0 as f64).powi(2) + (q.1 as f64 - p.1 as f64).powi(2))
-                .sqrt()
-                .round() as usize
-        }
+    fn h_score(&self, point: Point) -> usize {
+        Self::euclidean_distance(point, self.end)
+    }
 
-        fn reconstruct_path(
-            came_from: &HashMap<(usize, usize), (usize, usize)>,
-            mut current: (usize, usize),
-        ) -> Option<VecDeque<(usize, usize)>> {
-            let mut path = VecDeque::from([current]);
+    fn euclidean_distance(p: Point, q: Point) -> usize {
+        ((q.x as f64 - p.x as f64).powi(2) + (q.y as f64 - p.y as f64).powi(2))
+            .sqrt()
+            .round() as usize
+    }
 
-            while came_from.contains_key(&current) {
-                current = came_from.get(&current).copied()?;
-                path.push_front(current);
-            }
+    fn reconstruct_path(
+        came_from: &HashMap<Point, Point>,
+        mut current: Point,
+    ) -> Option<VecDeque<Point>> {
+        let mut path = VecDeque::from([current]);
 
-            Some(path)
+        while came_from.contains_key(&current) {
+            current = came_from.get(&current).copied()?;
+            path.push_front(current);
         }
+
+        Some(path)
     }
 }
 
 fn part_one(heat_map: &HeatMap) -> usize {
-    (0..heat_map.len())
-        .flat_map(|i| (0..heat_map.len()).map(move |j| (i, j)))
-        .find(|&(i, j)| {
+    heat_map
+        .points()
+        .find(|&point| {
             heat_map
-                .get((i, j))
+                .get(point)
                 .map(|elevation| elevation == 'S')
                 .unwrap_or_default()
         })
@@ -190,11 +202,11 @@ fn part_one(heat_map: &HeatMap) -> usize {
 }
 
 fn part_two(heat_map: &HeatMap) -> usize {
-    (0..heat_map.len())
-        .flat_map(|i| (0..heat_map.len()).map(move |j| (i, j)))
-        .filter(|&(i, j)| {
+    heat_map
+        .points()
+        .filter(|&point| {
             heat_map
-                .get((i, j))
+                .get(point)
                 .map(|elevation| elevation == 'S' || elevation == 'a')
                 .unwrap_or_default()
         })
@@ -205,11 +217,97 @@ fn part_two(heat_map: &HeatMap) -> usize {
         .unwrap_or_default()
 }
 
-fn main() -> Result<()> {
-    let heat_map = HeatMap::from_stdin(io::stdin())?;
+/// Animates the search from whichever of `starts` has the shortest path.
+fn visualize(heat_map: &HeatMap, starts: &[char], animation: &mut Animation<'_>) -> Result<()> {
+    let start = heat_map
+        .points()
+        .filter(|&point| {
+            heat_map
+                .get(point)
+                .map(|elevation| starts.contains(&elevation))
+                .unwrap_or_default()
+        })
+        .flat_map(|start| heat_map.find_path(start).map(|path| (path.len(), start)))
+        .min()
+        .map(|(_, start)| start)
+        .ok_or_else(|| anyhow!("No path to the end!"))?;
+
+    let mut drawn = Ok(());
+    let path = heat_map.find_path_with(start, |open_set, reached| {
+        if drawn.is_ok() {
+            drawn = animation.frame(|| heat_map.render(open_set, reached, &VecDeque::new()));
+        }
+    });
+    drawn?;
+
+    let path = path.unwrap_or_default();
+    animation.finish(|| heat_map.render(&HashSet::new(), &HashMap::new(), &path))
+}
 
-    println!("Part one: {}", part_one(&heat_map));
-    println!("Part two: {}", part_two(&heat_map));
+impl Solution for Day12 {
+    const DAY: u8 = 12;
 
-    Ok(())
+    type Input = HeatMap;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn part_one(heat_map: &Self::Input, _params: &Params) -> Result<usize> {
+        Ok(part_one(heat_map))
+    }
+
+    fn part_two(heat_map: &Self::Input, _params: &Params) -> Result<usize> {
+        Ok(part_two(heat_map))
+    }
+
+    fn visualize(
+        heat_map: &Self::Input,
+        _params: &Params,
+        part: Part,
+        animation: &mut Animation<'_>,
+    ) -> Result<()> {
+        match part {
+            Part::One => visualize(heat_map, &['S'], animation),
+            Part::Two => visualize(heat_map, &['S', 'a'], animation),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../examples/day12-1.txt");
+
+    #[test]
+    fn part_one_example() -> Result<()> {
+        let input = <Day12 as Solution>::Input::from_text(EXAMPLE)?;
+        assert_eq!(Day12::part_one(&input, &Params::of::<Day12>())?, 31);
+        Ok(())
+    }
+
+    #[test]
+    fn part_two_example() -> Result<()> {
+        let input = <Day12 as Solution>::Input::from_text(EXAMPLE)?;
+        assert_eq!(Day12::part_two(&input, &Params::of::<Day12>())?, 29);
+        Ok(())
+    }
+
+    #[test]
+    fn visualizes_path() -> Result<()> {
+        let input = <Day12 as Solution>::Input::from_text(EXAMPLE)?;
+        let mut out = vec![];
+        let mut animation = Animation::new(&mut out, visual::Mode::Plain).every(usize::MAX);
+
+        Day12::visualize(&input, &Params::of::<Day12>(), Part::One, &mut animation)?;
+        let frames = animation.frames();
+        drop(animation);
+
+        let out = String::from_utf8(out)?;
+        let last = out
+            .rsplit(&format!("Frame {frames}\n"))
+            .next()
+            .unwrap_or_default();
+        assert_eq!(last.matches(['#', 'E']).count(), 31);
+        Ok(())
+    }
 }
@@ -1,253 +1,242 @@
-use std::{cmp::Ordering, io};
+use std::{cmp::Ordering, fmt, io::BufRead, str::FromStr};
 
 use anyhow::Result;
 
-use day13::{DistressSignal, Packet};
+use crate::error::{ParseError, ParseResult};
+use crate::params::Params;
+use crate::records::records;
+use crate::trace::{self, Level};
+use crate::{PuzzleInput, Solution};
 
-mod day13 {
-    use std::{
-        cmp::Ordering,
-        fmt,
-        io::{BufRead, Stdin},
-        str::FromStr,
-    };
+pub struct Day13;
 
-    use anyhow::{anyhow, Error, Result};
+/// Deepest list nesting accepted, so that parsing and comparing packets cannot
+/// overflow the stack.
+const MAX_PACKET_DEPTH: usize = 100;
 
-    #[derive(Clone, PartialEq)]
-    enum PacketData {
-        Integer(u8),
-        List(Vec<PacketData>),
-    }
-
-    #[derive(Clone, PartialEq)]
-    pub struct Packet {
-        data: Vec<PacketData>,
-    }
+#[derive(Clone, PartialEq)]
+enum PacketData {
+    Integer(u8),
+    List(Vec<PacketData>),
+}
 
-    #[derive(Clone)]
-    pub struct DistressSignal {
-        packets: Vec<(Packet, Packet)>,
-    }
+#[derive(Clone, PartialEq)]
+pub struct Packet {
+    data: Vec<PacketData>,
+}
 
-    fn parse<T>(str: &str) -> Result<Vec<T>>
-    where
-        T: FromStr + FromStr<Err = Error>,
-    {
-        let mut data = vec![];
+#[derive(Clone)]
+pub struct DistressSignal {
+    packets: Vec<(Packet, Packet)>,
+}
 
-        let mut data_start = None;
-        let mut list_depth = 0;
+fn parse<T>(str: &str) -> ParseResult<Vec<T>>
+where
+    T: FromStr + FromStr<Err = ParseError>,
+{
+    let mut data = vec![];
 
-        for (idx, char) in str.char_indices() {
-            if char.is_ascii_digit() {
-                if list_depth == 0 && data_start.is_none() {
-                    data_start = Some(idx);
-                }
-            } else if char == ',' {
-                if list_depth == 0 && data_start.is_some() {
-                    data.push(str[data_start.unwrap_or(idx)..idx].parse()?);
-                    data_start = None;
-                }
-            } else if char == '[' {
-                list_depth += 1;
-                if list_depth == 1 && data_start.is_none() {
-                    data_start = Some(idx);
-                }
-            } else if char == ']' {
-                list_depth -= 1;
+    let mut data_start = None;
+    let mut list_depth = 0;
 
-                if list_depth == 0 {
-                    data.push(str[data_start.unwrap_or(idx)..=idx].parse()?);
-                    data_start = None;
-                }
-            } else {
+    for (idx, char) in str.char_indices() {
+        if char.is_ascii_digit() {
+            if list_depth == 0 && data_start.is_none() {
+                data_start = Some(idx);
             }
-        }
+        } else if char == ',' {
+            if list_depth == 0 && data_start.is_some() {
+                data.push(str[data_start.unwrap_or(idx)..idx].parse()?);
+                data_start = None;
+            }
+        } else if char == '[' {
+            list_depth += 1;
+            if list_depth == 1 && data_start.is_none() {
+                data_start = Some(idx);
+            }
+        } else if char == ']' {
+            list_depth -= 1;
 
-        if let Some(start) = data_start {
-            data.push(str[start..].parse()?);
+            if list_depth == 0 {
+                data.push(str[data_start.unwrap_or(idx)..=idx].parse()?);
+                data_start = None;
+            }
         }
-
-        Ok(data)
     }
 
-    impl fmt::Debug for PacketData {
-        fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
-            match self {
-                Self::Integer(int) => write!(fmt, "{int:?}"),
-                Self::List(list) => fmt.debug_list().entries(list.iter()).finish(),
-            }
-        }
+    if let Some(start) = data_start {
+        data.push(str[start..].parse()?);
     }
 
-    impl fmt::Debug for Packet {
-        fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
-            fmt.debug_list().entries(self.data.iter()).finish()
+    Ok(data)
+}
+
+impl fmt::Debug for PacketData {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Integer(int) => write!(fmt, "{int:?}"),
+            Self::List(list) => fmt.debug_list().entries(list.iter()).finish(),
         }
     }
+}
 
-    impl fmt::Debug for DistressSignal {
-        fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
-            let mut sep = false;
-
-            for (first, second) in self.packets.iter() {
-                if sep {
-                    writeln!(fmt, "\n")?;
-                }
+impl fmt::Debug for Packet {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_list().entries(self.data.iter()).finish()
+    }
+}
 
-                writeln!(fmt, "{first:?}")?;
-                write!(fmt, "{second:?}")?;
+impl fmt::Debug for DistressSignal {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut sep = false;
 
-                sep = true;
+        for (first, second) in self.packets.iter() {
+            if sep {
+                writeln!(fmt, "\n")?;
             }
 
-            Ok(())
+            writeln!(fmt, "{first:?}")?;
+            write!(fmt, "{second:?}")?;
+
+            sep = true;
         }
+
+        Ok(())
     }
+}
 
-    impl FromStr for PacketData {
-        type Err = Error;
-
-        fn from_str(str: &str) -> Result<Self> {
-            if str.starts_with('[') && str.ends_with(']') {
-                parse(&str[1..str.len() - 1]).map(Self::List)
-            } else if let Ok(int) = str
-                .chars()
-                .take_while(|char| char.is_ascii_digit())
-                .collect::<String>()
-                .parse()
-            {
-                Ok(Self::Integer(int))
-            } else {
-                Err(anyhow!("Unknown packet data format!"))
-            }
+impl FromStr for PacketData {
+    type Err = ParseError;
+
+    fn from_str(str: &str) -> ParseResult<Self> {
+        if str.starts_with('[') && str.ends_with(']') {
+            parse(&str[1..str.len() - 1]).map(Self::List)
+        } else if let Ok(int) = str
+            .chars()
+            .take_while(|char| char.is_ascii_digit())
+            .collect::<String>()
+            .parse()
+        {
+            Ok(Self::Integer(int))
+        } else {
+            Err(ParseError::invalid("Unknown packet data format!", str))
         }
     }
+}
 
-    impl FromStr for Packet {
-        type Err = Error;
+impl FromStr for Packet {
+    type Err = ParseError;
+
+    fn from_str(str: &str) -> ParseResult<Self> {
+        let depth = str
+            .chars()
+            .scan(0isize, |depth, char| {
+                match char {
+                    '[' => *depth += 1,
+                    ']' => *depth -= 1,
+                    _ => (),
+                }
 
-        fn from_str(str: &str) -> Result<Self> {
-            if str.starts_with('[') && str.ends_with(']') {
-                parse(&str[1..str.len() - 1]).map(|data| Self { data })
-            } else {
-                Err(anyhow!("Packet is not a list!"))
-            }
+                Some(*depth)
+            })
+            .max()
+            .unwrap_or_default();
+
+        if depth > MAX_PACKET_DEPTH as isize {
+            Err(ParseError::invalid("Packet is nested too deeply!", str))
+        } else if str.starts_with('[') && str.ends_with(']') {
+            parse(&str[1..str.len() - 1]).map(|data| Self { data })
+        } else {
+            Err(ParseError::invalid("Packet is not a list!", str))
         }
     }
+}
+
+impl PartialOrd for PacketData {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (PacketData::Integer(left), PacketData::Integer(right)) => match left.cmp(right) {
+                Ordering::Less => Some(Ordering::Less),
+                Ordering::Greater => Some(Ordering::Greater),
+                Ordering::Equal => None,
+            },
+            (PacketData::List(left), PacketData::List(right)) => {
+                for (left, right) in left.iter().zip(right.iter()) {
+                    match left.partial_cmp(right) {
+                        Some(ordering) => return Some(ordering),
+                        None => continue,
+                    }
+                }
 
-    impl PartialOrd for PacketData {
-        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-            match (self, other) {
-                (PacketData::Integer(left), PacketData::Integer(right)) => match left.cmp(right) {
+                match left.len().cmp(&right.len()) {
                     Ordering::Less => Some(Ordering::Less),
                     Ordering::Greater => Some(Ordering::Greater),
                     Ordering::Equal => None,
-                },
-                (PacketData::List(left), PacketData::List(right)) => {
-                    for (left, right) in left.iter().zip(right.iter()) {
-                        match left.partial_cmp(right) {
-                            Some(ordering) => return Some(ordering),
-                            None => continue,
-                        }
-                    }
-
-                    match left.len().cmp(&right.len()) {
-                        Ordering::Less => Some(Ordering::Less),
-                        Ordering::Greater => Some(Ordering::Greater),
-                        Ordering::Equal => None,
-                    }
-                }
-                (&PacketData::Integer(left), PacketData::List(_)) => {
-                    Self::List(vec![Self::Integer(left)]).partial_cmp(other)
-                }
-                (PacketData::List(_), &PacketData::Integer(right)) => {
-                    self.partial_cmp(&Self::List(vec![Self::Integer(right)]))
                 }
             }
+            (&PacketData::Integer(left), PacketData::List(_)) => {
+                Self::List(vec![Self::Integer(left)]).partial_cmp(other)
+            }
+            (PacketData::List(_), &PacketData::Integer(right)) => {
+                self.partial_cmp(&Self::List(vec![Self::Integer(right)]))
+            }
         }
     }
+}
 
-    impl PartialOrd for Packet {
-        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-            for (left, right) in self.data.iter().zip(other.data.iter()) {
-                match left.partial_cmp(right) {
-                    Some(ordering) => return Some(ordering),
-                    None => continue,
-                }
+impl PartialOrd for Packet {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        for (left, right) in self.data.iter().zip(other.data.iter()) {
+            match left.partial_cmp(right) {
+                Some(ordering) => return Some(ordering),
+                None => continue,
             }
+        }
 
-            match self.data.len().cmp(&other.data.len()) {
-                Ordering::Less => Some(Ordering::Less),
-                Ordering::Greater => Some(Ordering::Greater),
-                Ordering::Equal => None,
-            }
+        match self.data.len().cmp(&other.data.len()) {
+            Ordering::Less => Some(Ordering::Less),
+            Ordering::Greater => Some(Ordering::Greater),
+            Ordering::Equal => None,
         }
     }
+}
 
-    impl Packet {
-        pub fn from_list(list: Vec<u8>) -> Self {
-            Self {
-                data: vec![PacketData::List(
-                    list.into_iter().map(PacketData::Integer).collect(),
-                )],
-            }
+impl Packet {
+    pub fn from_list(list: Vec<u8>) -> Self {
+        Self {
+            data: vec![PacketData::List(
+                list.into_iter().map(PacketData::Integer).collect(),
+            )],
         }
     }
+}
 
-    impl DistressSignal {
-        pub fn from_stdin(stdin: Stdin) -> Result<Self> {
-            const PACKET_PAIR_LINE_LEN: u8 = 2;
-
-            let mut last_valid = true;
-
-            let packets = stdin
-                .lock()
-                .lines()
-                .map(|line| {
-                    (
-                        line.as_ref()
-                            .map(|line| line.is_empty())
-                            .map(|empty| !empty)
-                            .unwrap_or_default(),
-                        line,
-                    )
-                })
-                .take_while(|(valid, _)| {
-                    let take = *valid || last_valid;
-                    last_valid = *valid;
-                    take
-                })
-                .map(|(_, line)| line.map_err(|err| anyhow!("{err}")))
-                .collect::<Result<Vec<_>>>()?
-                .into_iter()
-                .filter(|line| !line.is_empty())
-                .collect::<Vec<_>>()
-                .chunks(PACKET_PAIR_LINE_LEN as usize)
-                .map(|packets| {
-                    packets
-                        .iter()
-                        .map(|packet| packet.as_str())
-                        .map(Packet::from_str)
-                        .collect::<Result<Vec<_>>>()
-                })
-                .collect::<Result<Vec<Vec<_>>>>()?
-                .into_iter()
-                .map(|packets| packets.into_iter())
-                .map(|mut packets| (packets.next().unwrap(), packets.next().unwrap()))
-                .collect();
-
-            Ok(Self { packets })
-        }
+impl PuzzleInput for DistressSignal {
+    fn from_reader(reader: impl BufRead) -> ParseResult<Self> {
+        let packets = records(reader)
+            .map(|record| {
+                let record = record?;
+                let mut packets = record.parse_lines(str::parse)?.into_iter();
+
+                match (packets.next(), packets.next(), packets.next()) {
+                    (Some(left), Some(right), None) => Ok((left, right)),
+                    _ => Err(ParseError::missing("Expected a pair of packets!")
+                        .with_line(record.line, &record.lines[0])),
+                }
+            })
+            .collect::<ParseResult<_>>()?;
 
-        pub fn packets(&self) -> &[(Packet, Packet)] {
-            self.packets.as_slice()
-        }
+        Ok(Self { packets })
+    }
+}
 
-        pub fn all_packets(&self) -> impl Iterator<Item = &Packet> {
-            self.packets.iter().flat_map(|(i, j)| [i, j])
-        }
+impl DistressSignal {
+    pub fn packets(&self) -> &[(Packet, Packet)] {
+        self.packets.as_slice()
+    }
+
+    pub fn all_packets(&self) -> impl Iterator<Item = &Packet> {
+        self.packets.iter().flat_map(|(i, j)| [i, j])
     }
 }
 
@@ -256,9 +245,11 @@ fn part_one(signal: &DistressSignal) -> usize {
         .packets()
         .iter()
         .enumerate()
-        .map(|(idx, packets)| (idx + 1, packets))
-        // .inspect(|(idx, (i, j))| println!("{idx}: {}", i < j))
-        .filter(|&(_, (i, j))| i < j)
+        .map(|(idx, (i, j))| (idx + 1, i < j))
+        .inspect(|(idx, ordered)| {
+            trace::event(Level::Debug, "pair", &[("idx", idx), ("ordered", ordered)])
+        })
+        .filter(|&(_, ordered)| ordered)
         .map(|(idx, _)| idx)
         .sum()
 }
@@ -295,11 +286,39 @@ fn part_two(signal: &DistressSignal) -> usize {
     .product()
 }
 
-fn main() -> Result<()> {
-    let signal = DistressSignal::from_stdin(io::stdin())?;
+impl Solution for Day13 {
+    const DAY: u8 = 13;
+
+    type Input = DistressSignal;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn part_one(signal: &Self::Input, _params: &Params) -> Result<usize> {
+        Ok(part_one(signal))
+    }
+
+    fn part_two(signal: &Self::Input, _params: &Params) -> Result<usize> {
+        Ok(part_two(signal))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../examples/day13-1.txt");
 
-    println!("Part one: {}", part_one(&signal));
-    println!("Part two: {}", part_two(&signal));
+    #[test]
+    fn part_one_example() -> Result<()> {
+        let input = <Day13 as Solution>::Input::from_text(EXAMPLE)?;
+        assert_eq!(Day13::part_one(&input, &Params::of::<Day13>())?, 13);
+        Ok(())
+    }
 
-    Ok(())
+    #[test]
+    fn part_two_example() -> Result<()> {
+        let input = <Day13 as Solution>::Input::from_text(EXAMPLE)?;
+        assert_eq!(Day13::part_two(&input, &Params::of::<Day13>())?, 140);
+        Ok(())
+    }
 }
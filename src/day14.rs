@@ -1,299 +1,276 @@
-use std::io;
-
-use anyhow::Result;
+use std::{fmt, io::BufRead, str::FromStr};
+
+use anyhow::{anyhow, Result};
+
+use crate::error::{ParseError, ParseResult};
+use crate::geometry::{BoundingBox, Point, Vec2};
+use crate::grid::Grid;
+use crate::params::{Param, Params};
+use crate::records::parse_lines;
+use crate::runner::Part;
+use crate::trace::{self, Level};
+use crate::visual::{self, Animation};
+use crate::{PuzzleInput, Solution};
+
+pub struct Day14;
+
+const FLOOR_OFFSET: isize = 2;
+
+/// Largest coordinate of a rock or the sand source, which keeps the cave, a
+/// dense grid twice as wide as it is deep, within a few dozen megabytes.
+pub const MAX_COORD: isize = 4096;
+
+#[repr(u8)]
+#[derive(Clone, Copy, PartialEq)]
+enum Tile {
+    Air,
+    Rock,
+    SandSource,
+    Sand,
+}
 
-use day14::Cave;
+#[derive(Clone)]
+struct Path {
+    coords: Vec<Point>,
+}
 
-mod day14 {
-    use std::{
-        collections::HashMap,
-        fmt,
-        io::{BufRead, Stdin},
-        str::FromStr,
-    };
+/// Scanned rock paths, from which a [`Cave`] is built around a sand source.
+pub struct Scan(Vec<Path>);
 
-    use anyhow::{anyhow, Error, Result};
+#[derive(Clone)]
+pub struct Cave {
+    grid: Grid<Tile>,
+    origin: Point,
+    sand_source: Point,
+    y_max: isize,
+}
 
-    #[repr(u8)]
-    #[derive(Clone, Copy, PartialEq)]
-    enum Tile {
-        Air,
-        Rock,
-        SandSource,
-        Sand,
+impl fmt::Display for Tile {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "{}", self.as_char())
     }
+}
 
-    #[derive(Clone, Copy, Eq, Hash, PartialEq)]
-    struct Point {
-        x: u16,
-        y: u8,
+impl Tile {
+    fn as_char(&self) -> char {
+        match self {
+            Self::Air => '.',
+            Self::Rock => '#',
+            Self::SandSource => '+',
+            Self::Sand => 'o',
+        }
     }
+}
 
-    #[derive(Clone)]
-    struct Path {
-        coords: Vec<Point>,
-    }
+impl fmt::Debug for Path {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut sep = None;
 
-    #[derive(Clone)]
-    pub struct Cave {
-        grid: HashMap<Point, Tile>,
-        y_max: u8,
+        self.coords.iter().try_for_each(|point| {
+            let res = write!(fmt, "{}{point}", sep.unwrap_or_default());
+            sep = Some(" -> ");
+            res
+        })
     }
+}
 
-    impl fmt::Debug for Tile {
-        fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
-            match self {
-                Self::Air => write!(fmt, "."),
-                Self::Rock => write!(fmt, "#"),
-                Self::SandSource => write!(fmt, "+"),
-                Self::Sand => write!(fmt, "o"),
-            }
-        }
+impl fmt::Debug for Cave {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "{}", self.render(&self.bounds()))
     }
+}
 
-    impl fmt::Debug for Point {
-        fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
-            write!(fmt, "{},{}", self.x, self.y)
-        }
-    }
+impl FromStr for Path {
+    type Err = ParseError;
 
-    impl fmt::Debug for Path {
-        fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
-            let mut sep = None;
+    fn from_str(coords: &str) -> ParseResult<Self> {
+        let coords = coords
+            .split(" -> ")
+            .map(|point| {
+                let parsed: Point = point.parse()?;
 
-            self.coords.iter().try_for_each(|point| {
-                let res = write!(fmt, "{}{:?}", sep.unwrap_or_default(), point);
-                sep = Some(" -> ");
-                res
+                match in_range(parsed) {
+                    true => Ok(parsed),
+                    false => Err(ParseError::invalid("Coordinate out of range!", point)),
+                }
             })
-        }
-    }
+            .collect::<ParseResult<Vec<_>>>()?;
 
-    impl fmt::Debug for Cave {
-        fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
-            // self.grid.iter().try_for_each(|row| {
-            //     row.iter()
-            //         .try_for_each(|tile| write!(fmt, "{tile:?}"))
-            //         .and_then(|_| writeln!(fmt))
-            // })
-
-            (0..11).try_for_each(|y| {
-                (488..=512)
-                    .map(|x| Point { x, y })
-                    .map(|point| self.get_with_floor(point).unwrap())
-                    .try_for_each(|tile| write!(fmt, "{tile:?}"))
-                    .and_then(|_| writeln!(fmt))
-            })
-        }
+        Ok(Self { coords })
     }
+}
 
-    impl FromStr for Point {
-        type Err = Error;
+impl PuzzleInput for Scan {
+    fn from_reader(reader: impl BufRead) -> ParseResult<Self> {
+        parse_lines(reader, str::parse).map(Self)
+    }
+}
 
-        fn from_str(point: &str) -> Result<Self> {
-            let mut split = point.split(',');
+impl Cave {
+    const DOWN: Vec2 = Vec2::new(0, 1);
+    const DOWN_LEFT: Vec2 = Vec2::new(-1, 1);
+    const DOWN_RIGHT: Vec2 = Vec2::new(1, 1);
 
-            let x = split
-                .next()
-                .ok_or_else(|| anyhow!("Missing x-coordinate!"))?
-                .parse()?;
+    fn from_paths(paths: &[Path], sand_source: Point) -> Self {
+        let rocks =
+            BoundingBox::from_points(paths.iter().flat_map(|path| path.coords.iter().copied()));
 
-            let y = split
-                .next()
-                .ok_or_else(|| anyhow!("Missing y-coordinate!"))?
-                .parse()?;
+        let y_max = rocks.map(|rocks| rocks.max.y).unwrap_or(sand_source.y);
 
-            Ok(Self { x, y })
-        }
-    }
+        // Wide and deep enough for the sand pile that builds up on the floor.
+        let y_floor = FLOOR_OFFSET + y_max;
+        let mut bounds = BoundingBox::new(sand_source);
 
-    impl FromStr for Path {
-        type Err = Error;
+        bounds.include(sand_source + Vec2::new(-y_floor, y_floor - 1));
+        bounds.include(sand_source + Vec2::new(y_floor, y_floor - 1));
 
-        fn from_str(coords: &str) -> Result<Self> {
-            let coords = coords
-                .split(" -> ")
-                .map(|point| point.parse())
-                .collect::<Result<Vec<_>>>()?;
-
-            Ok(Self { coords })
+        if let Some(rocks) = rocks {
+            bounds.include(rocks.min);
+            bounds.include(rocks.max);
         }
-    }
 
-    impl Point {
-        fn as_down(&self) -> Option<Self> {
-            #[allow(clippy::unnecessary_lazy_evaluations)]
-            (self.y < u8::MAX).then(|| Self {
-                x: self.x,
-                y: self.y + 1,
-            })
-        }
+        let mut cave = Self {
+            grid: Grid::new(bounds.width(), bounds.height(), Tile::Air),
+            origin: bounds.min,
+            sand_source,
+            y_max,
+        };
+
+        *cave.get_mut(sand_source).unwrap_or_else(|| unreachable!()) = Tile::SandSource;
+
+        paths.iter().for_each(|path| {
+            path.coords
+                .windows(2)
+                .flat_map(|points| match (points.first(), points.last()) {
+                    (Some(&i), Some(&j)) => BoundingBox::from_points([i, j]),
+                    _ => None,
+                })
+                .flat_map(|line| line.points())
+                .for_each(|point| {
+                    if let Some(tile) = cave.get_mut(point) {
+                        *tile = Tile::Rock;
+                    }
+                });
+        });
+
+        cave
+    }
 
-        fn as_down_left(&self) -> Option<Self> {
-            #[allow(clippy::unnecessary_lazy_evaluations)]
-            (self.x as isize - 1 > 0 && self.y < u8::MAX).then(|| Self {
-                x: self.x - 1,
-                y: self.y + 1,
-            })
+    pub fn drop_sand(&mut self) -> Option<()> {
+        // Rocks around the source may hold the sand up before it ever falls
+        // into the abyss.
+        if let Tile::Sand = self.get(self.sand_source)? {
+            return None;
         }
 
-        fn as_down_right(&self) -> Option<Self> {
-            #[allow(clippy::unnecessary_lazy_evaluations)]
-            (self.x < u16::MAX && self.y < u8::MAX).then(|| Self {
-                x: self.x + 1,
-                y: self.y + 1,
-            })
+        let mut sand = self.sand_source;
+
+        loop {
+            if let Tile::Air = self.get(sand + Self::DOWN)? {
+                sand += Self::DOWN;
+            } else if let Tile::Air = self.get(sand + Self::DOWN_LEFT)? {
+                sand += Self::DOWN_LEFT;
+            } else if let Tile::Air = self.get(sand + Self::DOWN_RIGHT)? {
+                sand += Self::DOWN_RIGHT;
+            } else {
+                break;
+            }
         }
-    }
 
-    impl Path {
-        fn x_coords(&self) -> impl Iterator<Item = u16> + '_ {
-            self.coords.iter().map(|point| point.x)
-        }
+        *self.get_mut(sand)? = Tile::Sand;
+        trace::event(Level::Trace, "grain", &[("at", &sand)]);
 
-        fn y_coords(&self) -> impl Iterator<Item = u8> + '_ {
-            self.coords.iter().map(|point| point.y)
-        }
+        Some(())
     }
 
-    impl Cave {
-        pub fn from_stdin(stdin: Stdin) -> Result<Self> {
-            stdin
-                .lock()
-                .lines()
-                .take_while(|line| {
-                    line.as_ref()
-                        .map(|line| line.is_empty())
-                        .map(|empty| !empty)
-                        .unwrap_or_default()
-                })
-                .map(|line| line.map_err(|err| anyhow!(err)))
-                .map(|line| line.and_then(|line| line.parse()))
-                .collect::<Result<Vec<_>>>()
-                .map(|paths| Self::from_paths(paths.as_slice()))
+    pub fn drop_sand_with_floor(&mut self) -> Option<()> {
+        if let Tile::Sand = self.get(self.sand_source)? {
+            return None;
         }
 
-        fn from_paths(paths: &[Path]) -> Self {
-            let x_max = paths
-                .iter()
-                .flat_map(|path| path.x_coords())
-                .max()
-                .unwrap_or(Self::sand_source().x);
-
-            let y_max = paths
-                .iter()
-                .flat_map(|path| path.y_coords())
-                .max()
-                .unwrap_or(Self::sand_source().y);
-
-            let mut cave = Self {
-                grid: (0..=y_max)
-                    .flat_map(|y| (0..=x_max).map(move |x| Point { x, y }))
-                    .map(|point| (point, Tile::Air))
-                    .collect(),
-                y_max,
-            };
-
-            *cave
-                .get_mut(Self::sand_source())
-                .unwrap_or_else(|| unreachable!()) = Tile::SandSource;
-
-            paths.iter().for_each(|path| {
-                path.coords
-                    .windows(2)
-                    .flat_map(|points| match (points.first(), points.last()) {
-                        (Some(i), Some(j)) => Some((i, j)),
-                        _ => None,
-                    })
-                    .for_each(|(i, j)| {
-                        let (x_min, x_max) = (i.x.min(j.x), i.x.max(j.x));
-                        let (y_min, y_max) = (i.y.min(j.y), i.y.max(j.y));
-
-                        (x_min..=x_max).for_each(|x| {
-                            if let Some(tile) = cave.get_mut(Point { x, y: y_min }) {
-                                *tile = Tile::Rock;
-                            }
-                        });
-
-                        (y_min..=y_max).for_each(|y| {
-                            if let Some(tile) = cave.get_mut(Point { x: x_min, y }) {
-                                *tile = Tile::Rock;
-                            }
-                        })
-                    });
-            });
-
-            cave
+        let mut sand = self.sand_source;
+
+        loop {
+            if let Some(Tile::Air) = self.get_with_floor(sand + Self::DOWN) {
+                sand += Self::DOWN;
+            } else if let Some(Tile::Air) = self.get_with_floor(sand + Self::DOWN_LEFT) {
+                sand += Self::DOWN_LEFT;
+            } else if let Some(Tile::Air) = self.get_with_floor(sand + Self::DOWN_RIGHT) {
+                sand += Self::DOWN_RIGHT;
+            } else {
+                break;
+            }
         }
 
-        pub fn drop_sand(&mut self) -> Option<()> {
-            let mut sand = Self::sand_source();
-
-            loop {
-                if let Tile::Air = self.get(sand.as_down()?)? {
-                    sand = sand.as_down()?;
-                } else if let Tile::Air = self.get(sand.as_down_left()?)? {
-                    sand = sand.as_down_left()?;
-                } else if let Tile::Air = self.get(sand.as_down_right()?)? {
-                    sand = sand.as_down_right()?;
-                } else {
-                    break;
-                }
-            }
+        *self.get_with_floor_mut(sand)? = Tile::Sand;
+        trace::event(Level::Trace, "grain", &[("at", &sand)]);
 
-            *self.get_mut(sand)? = Tile::Sand;
+        Some(())
+    }
 
-            Some(())
-        }
+    /// The whole cave, down to and including the floor.
+    fn bounds(&self) -> BoundingBox {
+        let mut bounds = BoundingBox::new(self.origin);
+        bounds.include(self.origin + Vec2::new(self.grid.width() as isize - 1, 0));
+        bounds.include(Point::new(self.origin.x, self.y_floor()));
+        bounds
+    }
 
-        pub fn drop_sand_with_floor(&mut self) -> Option<()> {
-            if let Tile::Sand = self.get(Self::sand_source())? {
-                return None;
-            }
+    /// Just the rocks and the sand source, which is all the sand can settle on
+    /// without a floor.
+    fn rock_bounds(&self) -> BoundingBox {
+        BoundingBox::from_points(
+            self.grid
+                .iter()
+                .filter(|(_, &tile)| tile != Tile::Air)
+                .map(|(point, _)| point + self.origin),
+        )
+        .unwrap_or(BoundingBox::new(self.sand_source))
+    }
 
-            let mut sand = Self::sand_source();
-
-            loop {
-                if let Some(Tile::Air) = self.get_with_floor(sand.as_down()?) {
-                    sand = sand.as_down()?;
-                } else if let Some(Tile::Air) = self.get_with_floor(sand.as_down_left()?) {
-                    sand = sand.as_down_left()?;
-                } else if let Some(Tile::Air) = self.get_with_floor(sand.as_down_right()?) {
-                    sand = sand.as_down_right()?;
-                } else {
-                    break;
-                }
-            }
+    fn render(&self, bounds: &BoundingBox) -> String {
+        visual::render(bounds, |point| {
+            self.get_with_floor(point)
+                .map(|tile| tile.as_char())
+                .unwrap_or('#')
+        })
+    }
 
-            *self.get_with_floor_mut(sand)? = Tile::Sand;
+    fn y_floor(&self) -> isize {
+        FLOOR_OFFSET + self.y_max
+    }
 
-            Some(())
-        }
+    fn get(&self, point: Point) -> Option<Tile> {
+        self.grid.get(point - self.origin).copied()
+    }
 
-        const fn sand_source() -> Point {
-            Point { x: 500, y: 0 }
-        }
+    fn get_with_floor(&self, point: Point) -> Option<Tile> {
+        (point.y < self.y_floor()).then(|| self.get(point).unwrap_or(Tile::Air))
+    }
 
-        fn y_floor(&self) -> u8 {
-            const FLOOR_OFFSET: u8 = 2;
-            FLOOR_OFFSET + self.y_max
-        }
+    fn get_mut(&mut self, point: Point) -> Option<&mut Tile> {
+        self.grid.get_mut(point - self.origin)
+    }
 
-        fn get(&self, point: Point) -> Option<Tile> {
-            self.grid.get(&point).copied()
+    fn get_with_floor_mut(&mut self, point: Point) -> Option<&mut Tile> {
+        match point.y < self.y_floor() {
+            true => self.get_mut(point),
+            false => None,
         }
+    }
+}
 
-        fn get_with_floor(&self, point: Point) -> Option<Tile> {
-            (point.y < self.y_floor()).then(|| self.get(point).unwrap_or(Tile::Air))
-        }
+fn in_range(point: Point) -> bool {
+    (0..=MAX_COORD).contains(&point.x) && (0..=MAX_COORD).contains(&point.y)
+}
 
-        fn get_mut(&mut self, point: Point) -> Option<&mut Tile> {
-            self.grid.get_mut(&point)
-        }
+/// The sand source, which must lie within the same bounds as the rocks.
+fn sand_source(params: &Params) -> Result<Point> {
+    let source = params.get("source")?;
 
-        fn get_with_floor_mut(&mut self, point: Point) -> Option<&mut Tile> {
-            (point.y < self.y_floor()).then(|| self.grid.entry(point).or_insert(Tile::Air))
-        }
+    match in_range(source) {
+        true => Ok(source),
+        false => Err(anyhow!("Sand source {source} is out of range!")),
     }
 }
 
@@ -311,11 +288,137 @@ fn part_two(mut cave: Cave) -> usize {
         .unwrap_or_default()
 }
 
-fn main() -> Result<()> {
-    let cave = Cave::from_stdin(io::stdin())?;
+/// Draws the cave within `bounds` after every grain of sand comes to rest.
+fn visualize(
+    mut cave: Cave,
+    drop_sand: fn(&mut Cave) -> Option<()>,
+    bounds: BoundingBox,
+    animation: &mut Animation<'_>,
+) -> Result<()> {
+    while drop_sand(&mut cave).is_some() {
+        animation.frame(|| cave.render(&bounds))?;
+    }
+
+    animation.finish(|| cave.render(&bounds))
+}
+
+impl Solution for Day14 {
+    const DAY: u8 = 14;
+
+    type Input = Scan;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    const PARAMS: &'static [Param] = &[("source", "500,0")];
+
+    fn part_one(scan: &Self::Input, params: &Params) -> Result<usize> {
+        Ok(part_one(Cave::from_paths(&scan.0, sand_source(params)?)))
+    }
+
+    fn part_two(scan: &Self::Input, params: &Params) -> Result<usize> {
+        Ok(part_two(Cave::from_paths(&scan.0, sand_source(params)?)))
+    }
+
+    fn visualize(
+        scan: &Self::Input,
+        params: &Params,
+        part: Part,
+        animation: &mut Animation<'_>,
+    ) -> Result<()> {
+        let cave = Cave::from_paths(&scan.0, sand_source(params)?);
+
+        match part {
+            Part::One => {
+                let bounds = cave.rock_bounds();
+                visualize(cave, Cave::drop_sand, bounds, animation)
+            }
+            Part::Two => {
+                let bounds = cave.bounds();
+                visualize(cave, Cave::drop_sand_with_floor, bounds, animation)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    println!("Part one: {}", part_one(cave.clone()));
-    println!("Part two: {}", part_two(cave));
+    const EXAMPLE: &str = include_str!("../examples/day14-1.txt");
 
-    Ok(())
+    #[test]
+    fn part_one_example() -> Result<()> {
+        let input = <Day14 as Solution>::Input::from_text(EXAMPLE)?;
+        assert_eq!(Day14::part_one(&input, &Params::of::<Day14>())?, 24);
+        Ok(())
+    }
+
+    #[test]
+    fn part_two_example() -> Result<()> {
+        let input = <Day14 as Solution>::Input::from_text(EXAMPLE)?;
+        assert_eq!(Day14::part_two(&input, &Params::of::<Day14>())?, 93);
+        Ok(())
+    }
+
+    #[test]
+    fn stops_once_the_source_is_blocked() -> Result<()> {
+        let input = <Day14 as Solution>::Input::from_text("490,2 -> 510,2\n")?;
+        assert_eq!(Day14::part_one(&input, &Params::of::<Day14>())?, 4);
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_far_off_sources() -> Result<()> {
+        let input = <Day14 as Solution>::Input::from_text(EXAMPLE)?;
+        let params = Params::of::<Day14>().with("source", "3000000000,0")?;
+
+        let err = Day14::part_two(&input, &params).err();
+        assert_eq!(
+            err.map(|err| err.to_string()).as_deref(),
+            Some("Sand source 3000000000,0 is out of range!")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_far_off_rocks() {
+        let err = <Day14 as Solution>::Input::from_text("0,0 -> 3000000000,0\n").err();
+        let err = err
+            .as_ref()
+            .map(|err| (err.message(), err.token(), err.line()));
+
+        assert_eq!(
+            err,
+            Some(("Coordinate out of range!", Some("3000000000,0"), Some(1)))
+        );
+    }
+
+    #[test]
+    fn visualizes_part_one_example() -> Result<()> {
+        let input = <Day14 as Solution>::Input::from_text(EXAMPLE)?;
+        let mut out = vec![];
+        let mut animation = Animation::new(&mut out, visual::Mode::Plain).every(usize::MAX);
+
+        Day14::visualize(&input, &Params::of::<Day14>(), Part::One, &mut animation)?;
+        assert_eq!(animation.frames(), 25);
+        drop(animation);
+
+        let out = String::from_utf8(out)?;
+        assert!(out.ends_with(
+            "Frame 25
+......+...
+..........
+......o...
+.....ooo..
+....#ooo##
+...o#ooo#.
+..###ooo#.
+....oooo#.
+.o.ooooo#.
+#########.
+
+"
+        ));
+        Ok(())
+    }
 }
@@ -1,103 +1,79 @@
-use std::io;
 use std::io::BufRead;
-use std::io::Stdin;
 use std::ops::RangeInclusive;
 use std::str::FromStr;
 
-use anyhow::anyhow;
-use anyhow::Error;
 use anyhow::Result;
 
+use crate::error::{number, ParseError, ParseResult};
+use crate::geometry::Point;
+use crate::params::{Param, Params};
+use crate::records::parse_lines;
+use crate::{PuzzleInput, Solution, Unimplemented};
+
+pub struct Day15;
+
 struct Sensor {
     position: Point,
     beacon: Point,
 }
 
-#[derive(Clone, Debug, Eq, Hash, PartialEq)]
-struct Point {
-    x: isize,
-    y: isize,
-}
-
-struct SubterraneanTunnels {
+pub struct SubterraneanTunnels {
     sensors: Vec<Sensor>,
 }
 
-impl FromStr for Point {
-    type Err = Error;
-
-    fn from_str(point: &str) -> Result<Self> {
-        let mut split = point.trim().split(", ");
+fn parse_point(point: &str) -> ParseResult<Point> {
+    let mut split = point.trim().split(", ");
 
-        let x = split
-            .next()
-            .map(|x| x.split('='))
-            .and_then(|x| x.last())
-            .map(|x| x.parse())
-            .ok_or_else(|| anyhow!("Missing x-coordinate!"))??;
+    let x = split
+        .next()
+        .map(|x| x.split('='))
+        .and_then(|mut x| x.next_back())
+        .ok_or_else(|| ParseError::missing("Missing x-coordinate!"))
+        .and_then(number)?;
 
-        let y = split
-            .next()
-            .map(|y| y.split('='))
-            .and_then(|y| y.last())
-            .map(|y| y.parse())
-            .ok_or_else(|| anyhow!("Missing y-coordinate!"))??;
+    let y = split
+        .next()
+        .map(|y| y.split('='))
+        .and_then(|mut y| y.next_back())
+        .ok_or_else(|| ParseError::missing("Missing y-coordinate!").with_token(point.trim()))
+        .and_then(number)?;
 
-        Ok(Self { x, y })
-    }
+    Ok(Point::new(x, y))
 }
 
 impl FromStr for Sensor {
-    type Err = Error;
+    type Err = ParseError;
 
-    fn from_str(sensor: &str) -> Result<Self> {
+    fn from_str(sensor: &str) -> ParseResult<Self> {
         let mut split = sensor.trim().split(": ");
 
         let position = split
             .next()
-            .map(|position| position.split_at("Sensor at".len()))
-            .map(|(_, position)| position)
-            .map(|position| position.parse())
-            .ok_or_else(|| anyhow!("Missing sensor position!"))??;
+            .and_then(|position| position.strip_prefix("Sensor at"))
+            .ok_or_else(|| ParseError::missing("Missing sensor position!").with_token(sensor))
+            .and_then(parse_point)?;
 
         let beacon = split
             .next()
-            .map(|beacon| beacon.split_at("closest beacon is at".len()))
-            .map(|(_, beacon)| beacon)
-            .map(|beacon| beacon.parse())
-            .ok_or_else(|| anyhow!("Missing closest beacon location!"))??;
+            .and_then(|beacon| beacon.strip_prefix("closest beacon is at"))
+            .ok_or_else(|| {
+                ParseError::missing("Missing closest beacon location!").with_token(sensor)
+            })
+            .and_then(parse_point)?;
 
         Ok(Self { position, beacon })
     }
 }
 
-impl Point {
-    fn dist(&self, other: &Self) -> isize {
-        (self.x - other.x).abs() + (self.y - other.y).abs()
-    }
-}
-
-impl SubterraneanTunnels {
-    fn from_stdin(stdin: Stdin) -> Result<Self> {
-        let sensors = stdin
-            .lock()
-            .lines()
-            .take_while(|line| {
-                line.as_ref()
-                    .map(|line| line.is_empty())
-                    .map(|empty| !empty)
-                    .unwrap_or_default()
-            })
-            .map(|line| line.map_err(|err| anyhow!(err)))
-            .collect::<Result<Vec<_>>>()?
-            .iter()
-            .map(String::as_str)
-            .map(Sensor::from_str)
-            .collect::<Result<_>>()?;
+impl PuzzleInput for SubterraneanTunnels {
+    fn from_reader(reader: impl BufRead) -> ParseResult<Self> {
+        let sensors = parse_lines(reader, str::parse)?;
 
         Ok(Self { sensors })
     }
+}
 
+impl SubterraneanTunnels {
     fn is_beacon(&self, position: &Point) -> bool {
         self.sensors
             .iter()
@@ -109,7 +85,7 @@ impl SubterraneanTunnels {
         self.sensors
             .iter()
             .map(|sensor| (&sensor.position, &sensor.beacon))
-            .any(|(sensor, beacon)| sensor.dist(position) <= sensor.dist(beacon))
+            .any(|(sensor, beacon)| sensor.manhattan(position) <= sensor.manhattan(beacon))
     }
 
     fn find_x_range(&self, y: isize) -> RangeInclusive<isize> {
@@ -120,7 +96,7 @@ impl SubterraneanTunnels {
             .iter()
             .map(|sensor| (&sensor.position, &sensor.beacon))
             .for_each(|(sensor, beacon)| {
-                let dist = sensor.dist(beacon);
+                let dist = sensor.manhattan(beacon) as isize;
 
                 if (y - beacon.y).abs() <= dist {
                     let middle = sensor.x;
@@ -137,21 +113,41 @@ impl SubterraneanTunnels {
 
     fn find_beaconless_locations(&self, y: isize) -> impl Iterator<Item = Point> + '_ {
         self.find_x_range(y)
-            .map(move |x| Point { x, y })
+            .map(move |x| Point::new(x, y))
             .filter(|position| !self.is_beacon(position))
             .filter(|position| self.is_within_dist(position))
     }
 }
 
-fn part_one(tunnels: &SubterraneanTunnels) -> usize {
-    const Y_TARGET: isize = 2_000_000;
-    tunnels.find_beaconless_locations(Y_TARGET).count()
+fn part_one(tunnels: &SubterraneanTunnels, y_target: isize) -> usize {
+    tunnels.find_beaconless_locations(y_target).count()
+}
+
+impl Solution for Day15 {
+    const DAY: u8 = 15;
+
+    type Input = SubterraneanTunnels;
+    type PartOne = usize;
+    type PartTwo = Unimplemented;
+
+    const PARAMS: &'static [Param] = &[("y", "2000000")];
+
+    fn part_one(tunnels: &Self::Input, params: &Params) -> Result<usize> {
+        Ok(part_one(tunnels, params.get("y")?))
+    }
 }
 
-fn main() -> Result<()> {
-    let tunnels = SubterraneanTunnels::from_stdin(io::stdin())?;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    println!("Part one: {}", part_one(&tunnels));
+    const EXAMPLE: &str = include_str!("../examples/day15-1.txt");
 
-    Ok(())
+    #[test]
+    fn part_one_example() -> Result<()> {
+        let input = <Day15 as Solution>::Input::from_text(EXAMPLE)?;
+        let params = Params::of::<Day15>().with("y", "10")?;
+        assert_eq!(Day15::part_one(&input, &params)?, 26);
+        Ok(())
+    }
 }
@@ -1,7 +1,14 @@
-use anyhow::{anyhow, Error, Result};
-use std::io::{self, BufRead};
+use anyhow::Result;
+use std::io::BufRead;
 use std::str::FromStr;
 
+use crate::error::{ParseError, ParseResult};
+use crate::params::Params;
+use crate::records::parse_lines;
+use crate::{PuzzleInput, Solution};
+
+pub struct Day2;
+
 #[derive(Clone)]
 enum Shape {
     Rock,
@@ -25,6 +32,11 @@ struct Strategy {
     player: Shape,
 }
 
+pub struct StrategyGuide {
+    instructions: Vec<Round>,
+    strategies: Vec<Strategy>,
+}
+
 impl Shape {
     fn from_opponent_strategy(shape: char) -> Option<Self> {
         match shape {
@@ -109,28 +121,30 @@ impl RoundResult {
 }
 
 impl FromStr for Round {
-    type Err = Error;
+    type Err = ParseError;
 
-    fn from_str(str: &str) -> Result<Self> {
+    fn from_str(str: &str) -> ParseResult<Self> {
         let mut split = str.split_whitespace();
 
-        let opponent_shape = Shape::from_opponent_strategy(
-            split
-                .next()
-                .map(|str| str.chars().next())
-                .ok_or_else(|| anyhow!("Missing opponent strategy!"))?
-                .ok_or_else(|| anyhow!("Missing opponent strategy character!"))?,
-        )
-        .ok_or_else(|| anyhow!("Invalid opponent strategy!"))?;
-
-        let player_shape = Shape::from_player_strategy(
-            split
-                .next()
-                .map(|str| str.chars().next())
-                .ok_or_else(|| anyhow!("Missing player strategy!"))?
-                .ok_or_else(|| anyhow!("Missing opponent strategy character!"))?,
-        )
-        .ok_or_else(|| anyhow!("Invalid player strategy!"))?;
+        let opponent = split
+            .next()
+            .ok_or_else(|| ParseError::missing("Missing opponent strategy!"))?;
+
+        let opponent_shape = opponent
+            .chars()
+            .next()
+            .and_then(Shape::from_opponent_strategy)
+            .ok_or_else(|| ParseError::invalid("Invalid opponent strategy!", opponent))?;
+
+        let player = split
+            .next()
+            .ok_or_else(|| ParseError::missing("Missing player strategy!"))?;
+
+        let player_shape = player
+            .chars()
+            .next()
+            .and_then(Shape::from_player_strategy)
+            .ok_or_else(|| ParseError::invalid("Invalid player strategy!", player))?;
 
         Ok(Self {
             opponent: opponent_shape,
@@ -140,28 +154,30 @@ impl FromStr for Round {
 }
 
 impl FromStr for Strategy {
-    type Err = Error;
+    type Err = ParseError;
 
-    fn from_str(str: &str) -> Result<Self> {
+    fn from_str(str: &str) -> ParseResult<Self> {
         let mut split = str.split_whitespace();
 
-        let opponent_shape = Shape::from_opponent_strategy(
-            split
-                .next()
-                .map(|str| str.chars().next())
-                .ok_or_else(|| anyhow!("Missing opponent strategy!"))?
-                .ok_or_else(|| anyhow!("Missing opponent strategy character!"))?,
-        )
-        .ok_or_else(|| anyhow!("Invalid opponent strategy!"))?;
+        let opponent = split
+            .next()
+            .ok_or_else(|| ParseError::missing("Missing opponent strategy!"))?;
 
-        let player_shape = split
+        let opponent_shape = opponent
+            .chars()
             .next()
-            .map(|str| str.chars().next())
-            .ok_or_else(|| anyhow!("Missing player strategy!"))?
-            .ok_or_else(|| anyhow!("Missing opponent strategy character!"))?;
+            .and_then(Shape::from_opponent_strategy)
+            .ok_or_else(|| ParseError::invalid("Invalid opponent strategy!", opponent))?;
 
-        Strategy::new(opponent_shape, player_shape)
-            .ok_or_else(|| anyhow!("Invalid player strategy!"))
+        let player = split
+            .next()
+            .ok_or_else(|| ParseError::missing("Missing player strategy!"))?;
+
+        player
+            .chars()
+            .next()
+            .and_then(|strategy| Strategy::new(opponent_shape, strategy))
+            .ok_or_else(|| ParseError::invalid("Invalid player strategy!", player))
     }
 }
 
@@ -188,34 +204,18 @@ impl Strategy {
     }
 }
 
-fn read_instructions() -> Result<Vec<Round>> {
-    let mut lines = io::stdin().lock().lines();
-    let mut instructions = vec![];
-
-    while let Some(Ok(line)) = lines.next() {
-        if line.is_empty() {
-            break;
-        }
-
-        instructions.push(line.parse()?);
-    }
+impl PuzzleInput for StrategyGuide {
+    fn from_reader(reader: impl BufRead) -> ParseResult<Self> {
+        let (instructions, strategies) =
+            parse_lines(reader, |line| Ok((line.parse()?, line.parse()?)))?
+                .into_iter()
+                .unzip();
 
-    Ok(instructions)
-}
-
-fn read_strategy_guide() -> Result<Vec<Strategy>> {
-    let mut lines = io::stdin().lock().lines();
-    let mut strategy_guide = vec![];
-
-    while let Some(Ok(line)) = lines.next() {
-        if line.is_empty() {
-            break;
-        }
-
-        strategy_guide.push(line.parse()?);
+        Ok(StrategyGuide {
+            instructions,
+            strategies,
+        })
     }
-
-    Ok(strategy_guide)
 }
 
 fn part_one(instructions: &[Round]) -> usize {
@@ -240,9 +240,39 @@ fn part_two(strategy_guide: &[Strategy]) -> usize {
         .sum()
 }
 
-fn main() -> Result<()> {
-    println!("Part one: {}", part_one(read_instructions()?.as_slice()));
-    println!("Part two: {}", part_two(read_strategy_guide()?.as_slice()));
+impl Solution for Day2 {
+    const DAY: u8 = 2;
+
+    type Input = StrategyGuide;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn part_one(guide: &Self::Input, _params: &Params) -> Result<usize> {
+        Ok(part_one(guide.instructions.as_slice()))
+    }
+
+    fn part_two(guide: &Self::Input, _params: &Params) -> Result<usize> {
+        Ok(part_two(guide.strategies.as_slice()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    Ok(())
+    const EXAMPLE: &str = include_str!("../examples/day02-1.txt");
+
+    #[test]
+    fn part_one_example() -> Result<()> {
+        let input = <Day2 as Solution>::Input::from_text(EXAMPLE)?;
+        assert_eq!(Day2::part_one(&input, &Params::of::<Day2>())?, 15);
+        Ok(())
+    }
+
+    #[test]
+    fn part_two_example() -> Result<()> {
+        let input = <Day2 as Solution>::Input::from_text(EXAMPLE)?;
+        assert_eq!(Day2::part_two(&input, &Params::of::<Day2>())?, 12);
+        Ok(())
+    }
 }
@@ -1,23 +1,41 @@
-use anyhow::{anyhow, Error, Result};
+use anyhow::{anyhow, Result};
 use std::collections::HashSet;
-use std::io::{self, BufRead};
+use std::io::BufRead;
 use std::str::FromStr;
 
+use crate::error::{ParseError, ParseResult};
+use crate::params::Params;
+use crate::records::parse_lines;
+use crate::{PuzzleInput, Solution};
+
+pub struct Day3;
+
 #[derive(Debug, Clone)]
 struct Item(char);
 
 #[derive(Debug)]
-struct Rucksack(Vec<Item>);
+pub struct Rucksack(Vec<Item>);
 
 #[derive(Debug)]
 struct Group<'a>([&'a Rucksack; 3]);
 
 impl FromStr for Rucksack {
-    type Err = Error;
+    type Err = ParseError;
+
+    fn from_str(str: &str) -> ParseResult<Self> {
+        if !str.len().is_multiple_of(2) {
+            return Err(ParseError::invalid(
+                "Rucksack does not have equal size compartments!",
+                str,
+            ));
+        }
 
-    fn from_str(str: &str) -> Result<Self> {
-        if str.len() % 2 != 0 {
-            return Err(anyhow!("Rucksack does not have equal size compartments!"));
+        if let Some((idx, char)) = str
+            .chars()
+            .enumerate()
+            .find(|(_, char)| !char.is_ascii_alphabetic())
+        {
+            return Err(ParseError::invalid("Invalid item!", char).with_column(idx + 1));
         }
 
         Ok(Self(str.chars().map(Item).collect()))
@@ -77,19 +95,10 @@ impl Group<'_> {
     }
 }
 
-fn read_rucksacks() -> Result<Vec<Rucksack>> {
-    let mut lines = io::stdin().lock().lines();
-    let mut rucksacks = vec![];
-
-    while let Some(Ok(line)) = lines.next() {
-        if line.is_empty() {
-            break;
-        }
-
-        rucksacks.push(line.parse()?);
+impl PuzzleInput for Vec<Rucksack> {
+    fn from_reader(reader: impl BufRead) -> ParseResult<Self> {
+        parse_lines(reader, str::parse)
     }
-
-    Ok(rucksacks)
 }
 
 fn part_one(rucksacks: &[Rucksack]) -> usize {
@@ -102,7 +111,7 @@ fn part_one(rucksacks: &[Rucksack]) -> usize {
 
 fn part_two(rucksacks: &[Rucksack]) -> usize {
     rucksacks
-        .chunks(3)
+        .chunks_exact(3)
         .map(|group| [&group[0], &group[1], &group[2]])
         .map(Group)
         .flat_map(|group| group.find_badge())
@@ -110,11 +119,57 @@ fn part_two(rucksacks: &[Rucksack]) -> usize {
         .sum()
 }
 
-fn main() -> Result<()> {
-    let rucksacks = read_rucksacks()?;
+impl Solution for Day3 {
+    const DAY: u8 = 3;
+
+    type Input = Vec<Rucksack>;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn part_one(rucksacks: &Self::Input, _params: &Params) -> Result<usize> {
+        Ok(part_one(rucksacks.as_slice()))
+    }
+
+    fn part_two(rucksacks: &Self::Input, _params: &Params) -> Result<usize> {
+        match rucksacks.len().is_multiple_of(3) {
+            true => Ok(part_two(rucksacks.as_slice())),
+            false => Err(anyhow!("Last group has fewer than three rucksacks!")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../examples/day03-1.txt");
 
-    println!("Part one: {}", part_one(rucksacks.as_slice()));
-    println!("Part two: {}", part_two(rucksacks.as_slice()));
+    #[test]
+    fn part_one_example() -> Result<()> {
+        let input = <Day3 as Solution>::Input::from_text(EXAMPLE)?;
+        assert_eq!(Day3::part_one(&input, &Params::of::<Day3>())?, 157);
+        Ok(())
+    }
 
-    Ok(())
+    #[test]
+    fn part_two_example() -> Result<()> {
+        let input = <Day3 as Solution>::Input::from_text(EXAMPLE)?;
+        assert_eq!(Day3::part_two(&input, &Params::of::<Day3>())?, 70);
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_incomplete_groups() -> Result<()> {
+        let input = <Day3 as Solution>::Input::from_text("vJrwpWtwJgWrhcsFMMfFFhFp\n")?;
+        let params = Params::of::<Day3>();
+
+        assert_eq!(Day3::part_one(&input, &params)?, 16);
+
+        let err = Day3::part_two(&input, &params).err();
+        assert_eq!(
+            err.map(|err| err.to_string()).as_deref(),
+            Some("Last group has fewer than three rucksacks!")
+        );
+        Ok(())
+    }
 }
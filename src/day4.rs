@@ -1,8 +1,15 @@
-use anyhow::{anyhow, Error, Result};
-use std::io::{self, BufRead};
+use anyhow::Result;
+use std::io::BufRead;
 use std::ops::RangeInclusive;
 use std::str::FromStr;
 
+use crate::error::{number, ParseError, ParseResult};
+use crate::params::Params;
+use crate::records::parse_lines;
+use crate::{PuzzleInput, Solution};
+
+pub struct Day4;
+
 #[derive(Debug, Clone)]
 struct SectionAssignment {
     start: usize,
@@ -10,47 +17,47 @@ struct SectionAssignment {
 }
 
 #[derive(Debug)]
-struct ElfPair {
+pub struct ElfPair {
     first: SectionAssignment,
     second: SectionAssignment,
 }
 
 impl FromStr for SectionAssignment {
-    type Err = Error;
+    type Err = ParseError;
 
-    fn from_str(str: &str) -> Result<Self> {
+    fn from_str(str: &str) -> ParseResult<Self> {
         let mut split = str.split('-');
 
-        let start = usize::from_str(
-            split
-                .next()
-                .ok_or_else(|| anyhow!("Missing first section ID!"))?,
-        )?;
+        let start = split
+            .next()
+            .ok_or_else(|| ParseError::missing("Missing first section ID!"))
+            .and_then(number)?;
 
-        let end = usize::from_str(
-            split
-                .next()
-                .ok_or_else(|| anyhow!("Missing last section ID!"))?,
-        )?;
+        let end = split
+            .next()
+            .ok_or_else(|| ParseError::missing("Missing last section ID!").with_token(str))
+            .and_then(number)?;
 
         Ok(Self { start, end })
     }
 }
 
 impl FromStr for ElfPair {
-    type Err = Error;
+    type Err = ParseError;
 
-    fn from_str(str: &str) -> Result<Self> {
+    fn from_str(str: &str) -> ParseResult<Self> {
         let mut split = str.split(',');
 
         let first = split
             .next()
-            .ok_or_else(|| anyhow!("Missing first section assignment!"))?
+            .ok_or_else(|| ParseError::missing("Missing first section assignment!"))?
             .parse()?;
 
         let second = split
             .next()
-            .ok_or_else(|| anyhow!("Missing second section assignment!"))?
+            .ok_or_else(|| {
+                ParseError::missing("Missing second section assignment!").with_token(str)
+            })?
             .parse()?;
 
         Ok(Self { first, second })
@@ -85,19 +92,10 @@ impl ElfPair {
     }
 }
 
-fn read_elf_pairs() -> Result<Vec<ElfPair>> {
-    let mut lines = io::stdin().lock().lines();
-    let mut pairs = vec![];
-
-    while let Some(Ok(line)) = lines.next() {
-        if line.is_empty() {
-            break;
-        }
-
-        pairs.push(line.parse()?);
+impl PuzzleInput for Vec<ElfPair> {
+    fn from_reader(reader: impl BufRead) -> ParseResult<Self> {
+        parse_lines(reader, str::parse)
     }
-
-    Ok(pairs)
 }
 
 fn part_one(pairs: &[ElfPair]) -> usize {
@@ -111,11 +109,39 @@ fn part_two(pairs: &[ElfPair]) -> usize {
     pairs.iter().filter(|&pair| pair.any_overlap()).count()
 }
 
-fn main() -> Result<()> {
-    let pairs = read_elf_pairs()?;
+impl Solution for Day4 {
+    const DAY: u8 = 4;
 
-    println!("Part one: {}", part_one(pairs.as_slice()));
-    println!("Part two: {}", part_two(pairs.as_slice()));
+    type Input = Vec<ElfPair>;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn part_one(pairs: &Self::Input, _params: &Params) -> Result<usize> {
+        Ok(part_one(pairs.as_slice()))
+    }
 
-    Ok(())
+    fn part_two(pairs: &Self::Input, _params: &Params) -> Result<usize> {
+        Ok(part_two(pairs.as_slice()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../examples/day04-1.txt");
+
+    #[test]
+    fn part_one_example() -> Result<()> {
+        let input = <Day4 as Solution>::Input::from_text(EXAMPLE)?;
+        assert_eq!(Day4::part_one(&input, &Params::of::<Day4>())?, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn part_two_example() -> Result<()> {
+        let input = <Day4 as Solution>::Input::from_text(EXAMPLE)?;
+        assert_eq!(Day4::part_two(&input, &Params::of::<Day4>())?, 4);
+        Ok(())
+    }
 }
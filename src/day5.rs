@@ -1,5 +1,12 @@
 use anyhow::{anyhow, Result};
 use std::io::{self, BufRead};
+use std::str::FromStr;
+
+use crate::error::{number, ParseError, ParseResult};
+use crate::params::Params;
+use crate::{PuzzleInput, Solution};
+
+pub struct Day5;
 
 #[derive(Debug, Clone)]
 struct Crate(char);
@@ -20,6 +27,12 @@ struct Rearrangement {
     destination: usize,
 }
 
+#[derive(Debug)]
+pub struct Procedure {
+    stacks: Vec<Stack>,
+    rearrangements: Vec<Rearrangement>,
+}
+
 impl CrateMover9000 {
     fn rearrange(stacks: &mut [Stack], rearrangement: &Rearrangement) -> Option<()> {
         (0..rearrangement.stack_len)
@@ -46,7 +59,7 @@ impl CrateMover9001 {
         stacks
             .get_mut(rearrangement.source - 1)?
             .0
-            .drain(source_len - rearrangement.stack_len..)
+            .drain(source_len.checked_sub(rearrangement.stack_len)?..)
             .collect::<Vec<_>>()
             .into_iter()
             .map(|item| {
@@ -58,11 +71,14 @@ impl CrateMover9001 {
     }
 }
 
-fn read_stacks() -> Result<Vec<Stack>> {
-    let mut lines = io::stdin().lock().lines();
+fn read_stacks(
+    lines: &mut impl Iterator<Item = (io::Result<String>, usize)>,
+) -> ParseResult<Vec<Stack>> {
     let mut rows = Vec::new();
 
-    while let Some(Ok(line)) = lines.next() {
+    for (line, _) in lines {
+        let line = line?;
+
         if line.is_empty() {
             break;
         }
@@ -70,7 +86,7 @@ fn read_stacks() -> Result<Vec<Stack>> {
         let mut row = Vec::new();
 
         line.char_indices().for_each(|(idx, char)| {
-            if ('A'..='Z').contains(&char) {
+            if char.is_ascii_uppercase() {
                 row.push(Some(char));
             } else if idx % 2 != 0 && (idx + 1) % 4 != 0 {
                 row.push(None);
@@ -83,14 +99,15 @@ fn read_stacks() -> Result<Vec<Stack>> {
     }
 
     let stacks = (0..rows
-        .first()
-        .ok_or_else(|| anyhow!("No stacks given!"))?
-        .len())
+        .iter()
+        .map(Vec::len)
+        .max()
+        .ok_or_else(|| ParseError::missing("No stacks given!"))?)
         .rev()
         .map(|i| {
             rows.iter()
                 .rev()
-                .filter_map(|inner| inner[i])
+                .filter_map(|inner| inner.get(i).copied().flatten())
                 .map(Crate)
                 .collect()
         })
@@ -101,37 +118,74 @@ fn read_stacks() -> Result<Vec<Stack>> {
     Ok(stacks)
 }
 
-fn read_rearrangements() -> Result<Vec<Rearrangement>> {
-    let mut lines = io::stdin().lock().lines();
+fn read_rearrangements(
+    lines: &mut impl Iterator<Item = (io::Result<String>, usize)>,
+) -> ParseResult<Vec<Rearrangement>> {
     let mut rearrangements = Vec::new();
 
-    while let Some(Ok(line)) = lines.next() {
+    for (line, idx) in lines {
+        let line = line?;
+
         if line.is_empty() {
             break;
         }
 
-        let mut split = line.split_whitespace().flat_map(|str| str.parse::<usize>());
+        let rearrangement = line
+            .parse()
+            .map_err(|err: ParseError| err.with_line(idx, &line))?;
+
+        rearrangements.push(rearrangement);
+    }
+
+    Ok(rearrangements)
+}
+
+impl FromStr for Rearrangement {
+    type Err = ParseError;
+
+    fn from_str(str: &str) -> ParseResult<Self> {
+        let mut split = str.split_whitespace().skip(1).step_by(2);
 
         let stack_len = split
             .next()
-            .ok_or_else(|| anyhow!("No stack length given!"))?;
+            .ok_or_else(|| ParseError::missing("No stack length given!").with_token(str))
+            .and_then(number)?;
 
         let source = split
             .next()
-            .ok_or_else(|| anyhow!("No source stack given!"))?;
+            .ok_or_else(|| ParseError::missing("No source stack given!").with_token(str))
+            .and_then(stack_number)?;
 
         let destination = split
             .next()
-            .ok_or_else(|| anyhow!("No destination stack given!"))?;
+            .ok_or_else(|| ParseError::missing("No destination stack given!").with_token(str))
+            .and_then(stack_number)?;
 
-        rearrangements.push(Rearrangement {
+        Ok(Self {
             stack_len,
             source,
             destination,
         })
     }
+}
+
+/// Stacks are numbered from one.
+fn stack_number(token: &str) -> ParseResult<usize> {
+    match number(token)? {
+        0 => Err(ParseError::invalid("Invalid stack number!", token)),
+        number => Ok(number),
+    }
+}
 
-    Ok(rearrangements)
+impl PuzzleInput for Procedure {
+    fn from_reader(reader: impl BufRead) -> ParseResult<Self> {
+        let mut lines = reader.lines().zip(1..);
+
+        Ok(Self {
+            stacks: read_stacks(&mut lines)?,
+            rearrangements: read_rearrangements(&mut lines)?,
+        })
+    }
 }
 
 fn top_crates(stacks: &[Stack]) -> Option<String> {
@@ -146,7 +200,7 @@ fn part_one(stacks: &mut [Stack], rearrangements: &[Rearrangement]) -> Option<St
     rearrangements
         .iter()
         .map(|rearrangement| CrateMover9000::rearrange(stacks, rearrangement))
-        .collect::<Option<_>>()?;
+        .collect::<Option<()>>()?;
 
     top_crates(stacks)
 }
@@ -155,26 +209,52 @@ fn part_two(stacks: &mut [Stack], rearrangements: &[Rearrangement]) -> Option<St
     rearrangements
         .iter()
         .map(|rearrangement| CrateMover9001::rearrange(stacks, rearrangement))
-        .collect::<Option<_>>()?;
+        .collect::<Option<()>>()?;
 
     top_crates(stacks)
 }
 
-fn main() -> Result<()> {
-    let mut stacks = read_stacks()?;
-    let rearrangements = read_rearrangements()?;
+impl Solution for Day5 {
+    const DAY: u8 = 5;
+
+    type Input = Procedure;
+    type PartOne = String;
+    type PartTwo = String;
+
+    fn part_one(procedure: &Self::Input, _params: &Params) -> Result<String> {
+        part_one(
+            procedure.stacks.clone().as_mut_slice(),
+            procedure.rearrangements.as_slice(),
+        )
+        .ok_or_else(|| anyhow!("No stacks given!"))
+    }
+
+    fn part_two(procedure: &Self::Input, _params: &Params) -> Result<String> {
+        part_two(
+            procedure.stacks.clone().as_mut_slice(),
+            procedure.rearrangements.as_slice(),
+        )
+        .ok_or_else(|| anyhow!("No stacks given!"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    println!(
-        "Part one: {}",
-        part_one(stacks.clone().as_mut_slice(), rearrangements.as_slice())
-            .ok_or_else(|| anyhow!("No stacks given!"))?
-    );
+    const EXAMPLE: &str = include_str!("../examples/day05-1.txt");
 
-    println!(
-        "Part two: {}",
-        part_two(stacks.as_mut_slice(), rearrangements.as_slice())
-            .ok_or_else(|| anyhow!("No stacks given!"))?
-    );
+    #[test]
+    fn part_one_example() -> Result<()> {
+        let input = <Day5 as Solution>::Input::from_text(EXAMPLE)?;
+        assert_eq!(Day5::part_one(&input, &Params::of::<Day5>())?, "CMZ");
+        Ok(())
+    }
 
-    Ok(())
+    #[test]
+    fn part_two_example() -> Result<()> {
+        let input = <Day5 as Solution>::Input::from_text(EXAMPLE)?;
+        assert_eq!(Day5::part_two(&input, &Params::of::<Day5>())?, "MCD");
+        Ok(())
+    }
 }
@@ -1,16 +1,29 @@
-use anyhow::{anyhow, Error, Result};
-use std::env;
+use anyhow::{anyhow, Result};
+use std::io::BufRead;
 use std::str::FromStr;
 
+use crate::error::{ParseError, ParseResult};
+use crate::params::{Param, Params};
+use crate::{PuzzleInput, Solution};
+
+pub struct Day6;
+
 #[derive(Debug)]
-struct Signal(String);
+pub struct Signal(String);
 
 impl FromStr for Signal {
-    type Err = Error;
+    type Err = ParseError;
 
-    fn from_str(str: &str) -> Result<Self> {
-        if !str.chars().all(|char| char.is_ascii_lowercase()) {
-            return Err(anyhow!("Signal is not all lowercase ASCII!"));
+    fn from_str(str: &str) -> ParseResult<Self> {
+        if let Some((idx, char)) = str
+            .chars()
+            .enumerate()
+            .find(|(_, char)| !char.is_ascii_lowercase())
+        {
+            return Err(
+                ParseError::invalid("Signal is not all lowercase ASCII!", char)
+                    .with_column(idx + 1),
+            );
         }
 
         Ok(Self(str.to_owned()))
@@ -34,36 +47,70 @@ impl Signal {
     }
 }
 
-fn read_signal() -> Result<Signal> {
-    let signal = env::args()
-        .nth(1)
-        .ok_or_else(|| anyhow!("Missing signal!"))?;
+impl PuzzleInput for Signal {
+    fn from_reader(reader: impl BufRead) -> ParseResult<Self> {
+        let line = reader
+            .lines()
+            .next()
+            .ok_or_else(|| ParseError::missing("Missing signal!"))??;
+
+        line.parse()
+            .map_err(|err: ParseError| err.with_line(1, &line))
+    }
+}
+
+fn part_one(signal: &Signal, packet_start_len: usize) -> Option<usize> {
+    signal.find_packet_start(packet_start_len)
+}
 
-    Ok(Signal(signal))
+fn part_two(signal: &Signal, message_start_len: usize) -> Option<usize> {
+    signal.find_packet_start(message_start_len)
 }
 
-fn part_one(signal: &Signal) -> Option<usize> {
-    const PACKET_START_LEN: usize = 4;
-    signal.find_packet_start(PACKET_START_LEN)
+fn marker_len(params: &Params, name: &str) -> Result<usize> {
+    match params.get(name)? {
+        0 => Err(anyhow!("Marker length must be positive!")),
+        len => Ok(len),
+    }
 }
 
-fn part_two(signal: &Signal) -> Option<usize> {
-    const MESSAGE_START_LEN: usize = 14;
-    signal.find_packet_start(MESSAGE_START_LEN)
+impl Solution for Day6 {
+    const DAY: u8 = 6;
+
+    type Input = Signal;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    const PARAMS: &'static [Param] = &[("packet_len", "4"), ("message_len", "14")];
+
+    fn part_one(signal: &Self::Input, params: &Params) -> Result<usize> {
+        part_one(signal, marker_len(params, "packet_len")?)
+            .ok_or_else(|| anyhow!("No start-of-packet marker found!"))
+    }
+
+    fn part_two(signal: &Self::Input, params: &Params) -> Result<usize> {
+        part_two(signal, marker_len(params, "message_len")?)
+            .ok_or_else(|| anyhow!("No start-of-message marker found!"))
+    }
 }
 
-fn main() -> Result<()> {
-    let signal = read_signal()?;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    println!(
-        "Part one: {}",
-        part_one(&signal).ok_or_else(|| anyhow!("No start-of-packet marker found!"))?
-    );
+    const EXAMPLE: &str = include_str!("../examples/day06-1.txt");
 
-    println!(
-        "Part two: {}",
-        part_two(&signal).ok_or_else(|| anyhow!("No start-of-message marker found!"))?
-    );
+    #[test]
+    fn part_one_example() -> Result<()> {
+        let input = <Day6 as Solution>::Input::from_text(EXAMPLE)?;
+        assert_eq!(Day6::part_one(&input, &Params::of::<Day6>())?, 7);
+        Ok(())
+    }
 
-    Ok(())
+    #[test]
+    fn part_two_example() -> Result<()> {
+        let input = <Day6 as Solution>::Input::from_text(EXAMPLE)?;
+        assert_eq!(Day6::part_two(&input, &Params::of::<Day6>())?, 19);
+        Ok(())
+    }
 }
@@ -1,11 +1,17 @@
 use std::cell::RefCell;
 use std::cmp::Reverse;
 use std::collections::{BinaryHeap, HashMap};
-use std::io::{self, BufRead};
+use std::io::BufRead;
 use std::rc::{Rc, Weak};
 use std::str::FromStr;
 
-use anyhow::{anyhow, Error, Result};
+use anyhow::{anyhow, Result};
+
+use crate::error::{number, ParseError, ParseResult};
+use crate::params::{Param, Params};
+use crate::{PuzzleInput, Solution};
+
+pub struct Day7;
 
 #[derive(Debug)]
 enum Command {
@@ -27,17 +33,17 @@ struct FileNode {
 }
 
 #[derive(Clone, Debug)]
-struct File(Rc<RefCell<FileNode>>);
+pub struct File(Rc<RefCell<FileNode>>);
 
 impl FromStr for Command {
-    type Err = Error;
+    type Err = ParseError;
 
-    fn from_str(str: &str) -> Result<Self> {
+    fn from_str(str: &str) -> ParseResult<Self> {
         let mut chars = str.chars();
 
         let prompt = chars
             .next()
-            .ok_or_else(|| anyhow!("Missing command prompt!"))?;
+            .ok_or_else(|| ParseError::missing("Missing command prompt!"))?;
 
         let command = ""
             .chars()
@@ -46,30 +52,35 @@ impl FromStr for Command {
             .collect::<String>();
 
         if prompt != '$' {
-            return Err(anyhow!("Expected '$' as command prompt!"));
+            return Err(ParseError::invalid(
+                "Expected '$' as command prompt!",
+                prompt,
+            ));
         }
 
         match command.as_str() {
             "cd" => Ok(Self::ChangeDir(chars.skip(1).collect())),
             "ls" => Ok(Self::List),
-            _ => Err(anyhow!("Unknown command!")),
+            _ => Err(ParseError::invalid("Unknown command!", command)),
         }
     }
 }
 
 impl FromStr for File {
-    type Err = Error;
+    type Err = ParseError;
 
-    fn from_str(str: &str) -> Result<Self> {
-        if &str[0..3] == "dir" {
-            let dir_name = str.chars().skip(4).collect();
-            Ok(File::new_dir(dir_name))
+    fn from_str(str: &str) -> ParseResult<Self> {
+        if let Some(dir_name) = str.strip_prefix("dir ") {
+            Ok(File::new_dir(dir_name.to_owned()))
         } else {
             let mut split = str.split_whitespace();
-            let size = usize::from_str(split.next().ok_or_else(|| anyhow!("Missing file size!"))?)?;
+            let size = split
+                .next()
+                .ok_or_else(|| ParseError::missing("Missing file size!"))
+                .and_then(number)?;
             let file_name = split
                 .next()
-                .ok_or_else(|| anyhow!("Missing file name!"))?
+                .ok_or_else(|| ParseError::missing("Missing file name!").with_token(str))?
                 .to_owned();
 
             Ok(File::new_file(file_name, size))
@@ -103,10 +114,10 @@ impl File {
         self
     }
 
-    fn to_size(&self) -> usize {
+    fn to_size(&self) -> Result<usize> {
         match &self.0.borrow().data {
-            FileData::Reg(size) => *size,
-            FileData::Dir(files) => files.values().map(|file| file.to_size()).sum(),
+            FileData::Reg(size) => Ok(*size),
+            FileData::Dir(files) => checked_sum(files.values().map(|file| file.to_size())),
         }
     }
 
@@ -125,24 +136,24 @@ impl File {
         }
     }
 
-    fn to_parent(&self) -> Option<Result<Self>> {
+    fn to_parent(&self) -> Option<ParseResult<Self>> {
         self.0
             .borrow()
             .parent
             .clone()
             .map(|parent| parent.upgrade())
-            .map(|parent| parent.ok_or_else(|| anyhow!("Parent reference dropped!")))
+            .map(|parent| parent.ok_or_else(|| ParseError::missing("Parent reference dropped!")))
             .map(|parent| parent.map(Self))
     }
 
-    fn find_flat(&self, file: &str) -> Result<Option<Self>> {
+    fn find_flat(&self, file: &str) -> ParseResult<Option<Self>> {
         match &self.0.borrow().data {
             FileData::Dir(files) => Ok(files.get(file).map(|file| file.0.clone()).map(Self)),
-            _ => Err(anyhow!("File not a directory!")),
+            _ => Err(ParseError::invalid("File not a directory!", file)),
         }
     }
 
-    fn insert(&mut self, other: Self) -> Result<()> {
+    fn insert(&mut self, other: Self) -> ParseResult<()> {
         match &mut self.0.borrow_mut().data {
             FileData::Dir(contents) => {
                 let name = other.0.borrow().name.as_str().to_owned();
@@ -152,77 +163,105 @@ impl File {
 
                 Ok(())
             }
-            _ => Err(anyhow!("File not a directory!")),
+            _ => Err(ParseError::invalid(
+                "File not a directory!",
+                other.0.borrow().name.as_str(),
+            )),
         }
     }
 }
 
-fn read_files() -> Result<File> {
-    let mut lines = io::stdin().lock().lines();
-    let mut root = None;
-    let mut file = None;
+impl PuzzleInput for File {
+    fn from_reader(reader: impl BufRead) -> ParseResult<Self> {
+        let mut root = None;
+        let mut file = None;
+
+        let read_line = |file: &mut Option<File>, line: &str| -> ParseResult<()> {
+            if let Ok(cmd) = line.parse() {
+                if let Command::ChangeDir(dir) = cmd {
+                    *file = match dir.as_str() {
+                        ".." => Some(
+                            file.as_ref()
+                                .and_then(|file: &File| file.to_parent())
+                                .ok_or_else(|| {
+                                    ParseError::missing("File has no parent directory!")
+                                })??,
+                        ),
+                        _ => match file.as_ref() {
+                            Some(file) => file.find_flat(dir.as_str())?,
+                            None => Some(File::new_dir(dir)),
+                        },
+                    }
+                }
+            } else {
+                file.as_mut()
+                    .map(|file| file.insert(line.parse()?))
+                    .ok_or_else(|| ParseError::missing("No directory found!"))??;
+            }
 
-    while let Some(Ok(line)) = lines.next() {
-        if line.is_empty() {
-            break;
-        }
+            Ok(())
+        };
 
-        if let Ok(cmd) = line.parse() {
-            if let Command::ChangeDir(dir) = cmd {
-                file = match dir.as_str() {
-                    ".." => Some(
-                        file.as_ref()
-                            .and_then(|file: &File| file.to_parent())
-                            .ok_or_else(|| anyhow!("File has no parent directory!"))??,
-                    ),
-                    _ => match file.as_ref() {
-                        Some(file) => file.find_flat(dir.as_str())?,
-                        None => Some(File::new_dir(dir)),
-                    },
-                }
+        for (line, idx) in reader.lines().zip(1..) {
+            let line = line?;
+
+            if line.is_empty() {
+                break;
             }
-        } else {
-            file.as_mut()
-                .map(|file| file.insert(line.parse()?))
-                .ok_or_else(|| anyhow!("No directory found!"))??;
-        }
 
-        if root.is_none() && file.is_some() {
-            root = file.clone();
-        } else if root.is_none() {
-            break;
+            read_line(&mut file, &line).map_err(|err| err.with_line(idx, &line))?;
+
+            if root.is_none() && file.is_some() {
+                root = file.clone();
+            } else if root.is_none() {
+                break;
+            }
         }
-    }
 
-    root.ok_or_else(|| anyhow!("Missing root directory!"))
+        root.ok_or_else(|| ParseError::missing("Missing root directory!"))
+    }
 }
 
-fn part_one(root: &File) -> Result<usize> {
-    const MAX_DIR_SIZE: usize = 100000;
-
-    root.to_dirs(false)
-        .map(|dirs| dirs.into_iter())
-        .map(|dirs| dirs.map(|dir| dir.to_size()))
-        .map(|sizes| sizes.filter(|&size| size <= MAX_DIR_SIZE))
-        .map(|sizes| sizes.sum())
+fn checked_sum(mut sizes: impl Iterator<Item = Result<usize>>) -> Result<usize> {
+    sizes.try_fold(0usize, |sum, size| {
+        sum.checked_add(size?)
+            .ok_or_else(|| anyhow!("File sizes overflow!"))
+    })
 }
 
-fn part_two(root: &File) -> Result<Option<usize>> {
-    const TOTAL_DISK_SIZE: usize = 70000000;
-    const UPDATE_FREE_DISK_SIZE: usize = 30000000;
+fn part_one(root: &File, max_dir_size: usize) -> Result<usize> {
+    let sizes = root
+        .to_dirs(false)?
+        .into_iter()
+        .map(|dir| dir.to_size())
+        .collect::<Result<Vec<_>>>()?;
+
+    checked_sum(
+        sizes
+            .into_iter()
+            .filter(|&size| size <= max_dir_size)
+            .map(Ok),
+    )
+}
 
-    let unused_disk_size = TOTAL_DISK_SIZE - root.to_size();
+fn part_two(
+    root: &File,
+    total_disk_size: usize,
+    update_free_disk_size: usize,
+) -> Result<Option<usize>> {
+    let unused_disk_size = total_disk_size
+        .checked_sub(root.to_size()?)
+        .ok_or_else(|| anyhow!("Files exceed the disk size!"))?;
 
     let mut used_size = None;
     let mut used_sizes = root
-        .to_dirs(false)
-        .map(|dirs| dirs.into_iter())
-        .map(|dirs| dirs.map(|dir| dir.to_size()))
-        .map(|sizes| sizes.map(Reverse))
-        .map(|sizes| sizes.collect::<BinaryHeap<_>>())?;
+        .to_dirs(false)?
+        .into_iter()
+        .map(|dir| dir.to_size().map(Reverse))
+        .collect::<Result<BinaryHeap<_>>>()?;
 
     while let Some(size) = used_sizes.pop() {
-        if unused_disk_size + size.0 >= UPDATE_FREE_DISK_SIZE {
+        if unused_disk_size + size.0 >= update_free_disk_size {
             used_size = Some(size.0);
             break;
         }
@@ -231,14 +270,55 @@ fn part_two(root: &File) -> Result<Option<usize>> {
     Ok(used_size)
 }
 
-fn main() -> Result<()> {
-    let files = read_files()?;
+impl Solution for Day7 {
+    const DAY: u8 = 7;
+
+    type Input = File;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    const PARAMS: &'static [Param] = &[
+        ("max_dir_size", "100000"),
+        ("disk_size", "70000000"),
+        ("free_size", "30000000"),
+    ];
+
+    fn part_one(root: &Self::Input, params: &Params) -> Result<usize> {
+        part_one(root, params.get("max_dir_size")?)
+    }
+
+    fn part_two(root: &Self::Input, params: &Params) -> Result<usize> {
+        part_two(root, params.get("disk_size")?, params.get("free_size")?)?
+            .ok_or_else(|| anyhow!("No directory large enough!"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../examples/day07-1.txt");
 
-    println!("Part one: {}", part_one(&files)?);
-    println!(
-        "Part two: {}",
-        part_two(&files).map(|size| size.ok_or_else(|| anyhow!("No directory large enough!")))??
-    );
+    #[test]
+    fn part_one_example() -> Result<()> {
+        let input = <Day7 as Solution>::Input::from_text(EXAMPLE)?;
+        assert_eq!(Day7::part_one(&input, &Params::of::<Day7>())?, 95437);
+        Ok(())
+    }
+
+    #[test]
+    fn part_two_example() -> Result<()> {
+        let input = <Day7 as Solution>::Input::from_text(EXAMPLE)?;
+        assert_eq!(Day7::part_two(&input, &Params::of::<Day7>())?, 24933642);
+        Ok(())
+    }
 
-    Ok(())
+    #[test]
+    fn rejects_overflowing_sizes() -> Result<()> {
+        let text = format!("$ cd /\n$ ls\n{0} a\n{0} b\n", usize::MAX);
+        let input = <Day7 as Solution>::Input::from_text(&text)?;
+        assert!(Day7::part_one(&input, &Params::of::<Day7>()).is_err());
+        assert!(Day7::part_two(&input, &Params::of::<Day7>()).is_err());
+        Ok(())
+    }
 }
@@ -1,171 +1,117 @@
-use std::collections::HashSet;
-use std::io;
-
-use anyhow::Result;
+use std::collections::{HashSet, VecDeque};
+use std::io::BufRead;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Result};
+
+use crate::error::{number, ParseError, ParseResult};
+use crate::geometry::{BoundingBox, Direction, Point};
+use crate::params::{Param, Params};
+use crate::records::parse_lines;
+use crate::runner::Part;
+use crate::visual::{self, Animation};
+use crate::{PuzzleInput, Solution};
+
+pub struct Day9;
+
+/// Longest single motion. The head moves one step at a time, so this also
+/// keeps a line of input from taking forever to simulate.
+const MAX_LEN: usize = 10_000;
+
+#[derive(Debug)]
+pub struct Motion {
+    dir: Direction,
+    len: usize,
+}
 
-use day9::{Motion, Rope};
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Knot(Point);
 
-mod day9 {
-    use std::collections::VecDeque;
-    use std::io::{BufRead, Stdin};
-    use std::str::FromStr;
+#[derive(Debug)]
+pub struct Rope(VecDeque<Knot>);
 
-    use anyhow::{anyhow, Error, Result};
+impl FromStr for Motion {
+    type Err = ParseError;
 
-    #[derive(Clone, Copy, Debug)]
-    enum Direction {
-        Up,
-        Left,
-        Right,
-        Down,
-    }
-
-    #[derive(Debug)]
-    pub struct Motion {
-        dir: Direction,
-        len: usize,
-    }
+    fn from_str(str: &str) -> ParseResult<Self> {
+        let mut split = str.split_whitespace();
 
-    #[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
-    pub struct Knot(isize, isize);
+        let dir = split
+            .next()
+            .ok_or_else(|| ParseError::missing("Missing direction!"))?
+            .parse()?;
+        let len = split
+            .next()
+            .ok_or_else(|| ParseError::missing("Missing len!").with_token(str))
+            .and_then(number)?;
 
-    #[derive(Debug)]
-    pub struct Rope(VecDeque<Knot>);
-
-    impl FromStr for Direction {
-        type Err = Error;
-
-        fn from_str(str: &str) -> Result<Self> {
-            match str.len() {
-                1 => match str.chars().next().unwrap() {
-                    'U' => Ok(Self::Up),
-                    'L' => Ok(Self::Left),
-                    'R' => Ok(Self::Right),
-                    'D' => Ok(Self::Down),
-                    _ => Err(anyhow!("Invalid direction!")),
-                },
-                _ => Err(anyhow!("Expected single character for direction!")),
-            }
+        match len <= MAX_LEN {
+            true => Ok(Self { dir, len }),
+            false => Err(ParseError::invalid("Motion too long!", str)),
         }
     }
+}
 
-    impl FromStr for Motion {
-        type Err = Error;
-
-        fn from_str(str: &str) -> Result<Self> {
-            let mut split = str.split_whitespace();
+impl PuzzleInput for Vec<Motion> {
+    fn from_reader(reader: impl BufRead) -> ParseResult<Self> {
+        parse_lines(reader, str::parse)
+    }
+}
 
-            let dir = split
-                .next()
-                .map(|dir| dir.parse())
-                .ok_or_else(|| anyhow!("Missing direction!"))??;
-            let len = split
-                .next()
-                .map(usize::from_str)
-                .ok_or_else(|| anyhow!("Missing len!"))??;
+impl Knot {
+    fn move_head(&mut self, dir: Direction) {
+        self.0 += dir.as_offset();
+    }
 
-            Ok(Self { dir, len })
+    fn follow(&mut self, head: &Self) {
+        if self.0.chebyshev(&head.0) > 1 {
+            self.0 += (head.0 - self.0).signum();
         }
     }
+}
 
-    impl Motion {
-        pub fn from_stdin(stdin: Stdin) -> Result<Vec<Self>> {
-            let mut lines = stdin.lock().lines();
-            let mut motions = Vec::new();
-
-            while let Some(Ok(line)) = lines.next() {
-                if line.is_empty() {
-                    break;
-                }
-
-                motions.push(line.parse()?);
-            }
-
-            Ok(motions)
+impl Rope {
+    pub fn with_knots(knots: usize) -> Result<Self> {
+        match knots {
+            (2..) => Ok(Self((0..knots).map(|_| Knot::default()).collect())),
+            _ => Err(anyhow!("Minimum of two knots required!")),
         }
     }
 
-    impl Knot {
-        fn move_head(&mut self, dir: Direction) {
-            match dir {
-                Direction::Up => self.0 += 1,
-                Direction::Left => self.1 -= 1,
-                Direction::Right => self.1 += 1,
-                Direction::Down => self.0 -= 1,
-            }
-        }
+    pub fn step(&mut self, motion: &Motion) -> Option<Vec<Knot>> {
+        (0..motion.len)
+            .map(|_| self.move_once(motion.dir))
+            .collect()
+    }
 
-        fn follow(&mut self, head: &Self) {
-            let i_diff = head.0.abs_diff(self.0);
-            let j_diff = head.1.abs_diff(self.1);
+    /// Moves the head a single position, returning where the tail ends up.
+    fn move_once(&mut self, dir: Direction) -> Option<Knot> {
+        self.0.front_mut()?.move_head(dir);
 
-            if (i_diff == 2 && j_diff == 0) || (i_diff == 0 && j_diff == 2) {
-                self.step(head);
-            } else if i_diff > 1 || j_diff > 1 {
-                self.diagonal_step(head);
-            }
-        }
+        (1..self.0.len())
+            .map(|i| {
+                let head = self.0.get(i - 1).cloned()?;
+                let tail = self.0.get_mut(i)?;
 
-        fn step(&mut self, head: &Self) {
-            if head.0 > self.0 {
-                self.0 += 1;
-            } else if self.0 > head.0 {
-                self.0 -= 1;
-            } else if head.1 > self.1 {
-                self.1 += 1;
-            } else if self.1 > head.1 {
-                self.1 -= 1;
-            }
-        }
+                tail.follow(&head);
 
-        fn diagonal_step(&mut self, head: &Self) {
-            match head.0 > self.0 {
-                true => self.0 += 1,
-                _ => self.0 -= 1,
-            };
+                Some(())
+            })
+            .collect::<Option<()>>()?;
 
-            match head.1 > self.1 {
-                true => self.1 += 1,
-                _ => self.1 -= 1,
-            };
-        }
+        self.0.back().cloned()
     }
 
-    impl Rope {
-        pub fn with_knots(knots: usize) -> Result<Self> {
-            match knots {
-                (2..) => Ok(Self((0..knots).map(|_| Knot::default()).collect())),
-                _ => Err(anyhow!("Minimum of two knots required!")),
+    fn render(&self, bounds: &BoundingBox, visited: &HashSet<Knot>) -> String {
+        visual::render(bounds, |point| {
+            match self.0.iter().position(|knot| knot.0 == point) {
+                Some(0) => 'H',
+                Some(i) => char::from_digit(i as u32, 36).unwrap_or('*'),
+                None if point == Point::default() => 's',
+                None if visited.contains(&Knot(point)) => '#',
+                None => '.',
             }
-        }
-
-        pub fn step(&mut self, motion: &Motion) -> Option<Vec<Knot>> {
-            let knots_len = self.0.len();
-            let mut tails = Vec::with_capacity(motion.len);
-
-            (0..motion.len)
-                .map(|_| {
-                    self.0.front_mut()?.move_head(motion.dir);
-
-                    (1..knots_len)
-                        .map(|i| {
-                            let head = self.0.get(i - 1).cloned()?;
-                            let tail = self.0.get_mut(i)?;
-
-                            tail.follow(&head);
-
-                            Some(())
-                        })
-                        .collect::<Option<_>>()?;
-
-                    tails.push(self.0.back().cloned()?);
-
-                    Some(())
-                })
-                .collect::<Option<_>>()?;
-
-            Some(tails)
-        }
+        })
     }
 }
 
@@ -178,6 +124,32 @@ fn count_tails(mut rope: Rope, motions: &[Motion]) -> usize {
         .len()
 }
 
+/// Draws the rope after every single move of its head, on a board spanning
+/// everywhere the head goes. The knots never leave that area, as each one only
+/// ever moves towards the one before it.
+fn visualize(mut rope: Rope, motions: &[Motion], animation: &mut Animation<'_>) -> Result<()> {
+    let mut bounds = BoundingBox::new(Point::default());
+    motions
+        .iter()
+        .flat_map(|motion| (0..motion.len).map(|_| motion.dir))
+        .fold(Point::default(), |head, dir| {
+            bounds.include(head + dir.as_offset());
+            head + dir.as_offset()
+        });
+
+    let mut visited = HashSet::from([Knot::default()]);
+
+    motions
+        .iter()
+        .flat_map(|motion| (0..motion.len).map(|_| motion.dir))
+        .try_for_each(|dir| {
+            visited.extend(rope.move_once(dir));
+            animation.frame(|| rope.render(&bounds, &visited))
+        })?;
+
+    animation.finish(|| rope.render(&bounds, &visited))
+}
+
 fn part_one(rope: Rope, motions: &[Motion]) -> usize {
     count_tails(rope, motions)
 }
@@ -186,18 +158,91 @@ fn part_two(rope: Rope, motions: &[Motion]) -> usize {
     count_tails(rope, motions)
 }
 
-fn main() -> Result<()> {
-    let motions = Motion::from_stdin(io::stdin())?;
+impl Solution for Day9 {
+    const DAY: u8 = 9;
+
+    type Input = Vec<Motion>;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    const PARAMS: &'static [Param] = &[("knots_one", "2"), ("knots_two", "10")];
+
+    fn part_one(motions: &Self::Input, params: &Params) -> Result<usize> {
+        Ok(part_one(
+            Rope::with_knots(params.get("knots_one")?)?,
+            motions.as_slice(),
+        ))
+    }
+
+    fn part_two(motions: &Self::Input, params: &Params) -> Result<usize> {
+        Ok(part_two(
+            Rope::with_knots(params.get("knots_two")?)?,
+            motions.as_slice(),
+        ))
+    }
+
+    fn visualize(
+        motions: &Self::Input,
+        params: &Params,
+        part: Part,
+        animation: &mut Animation<'_>,
+    ) -> Result<()> {
+        let knots = match part {
+            Part::One => params.get("knots_one")?,
+            Part::Two => params.get("knots_two")?,
+        };
+
+        visualize(Rope::with_knots(knots)?, motions.as_slice(), animation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../examples/day09-1.txt");
+    const LARGER_EXAMPLE: &str = include_str!("../examples/day09-2.txt");
+
+    #[test]
+    fn part_one_example() -> Result<()> {
+        let input = <Day9 as Solution>::Input::from_text(EXAMPLE)?;
+        assert_eq!(Day9::part_one(&input, &Params::of::<Day9>())?, 13);
+        Ok(())
+    }
+
+    #[test]
+    fn part_two_example() -> Result<()> {
+        let input = <Day9 as Solution>::Input::from_text(EXAMPLE)?;
+        assert_eq!(Day9::part_two(&input, &Params::of::<Day9>())?, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn part_two_larger_example() -> Result<()> {
+        let input = <Day9 as Solution>::Input::from_text(LARGER_EXAMPLE)?;
+        assert_eq!(Day9::part_two(&input, &Params::of::<Day9>())?, 36);
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_overlong_motions() {
+        let err = <Day9 as Solution>::Input::from_text("R 18446744073709551615\n").err();
+        assert!(err.is_some_and(|err| err.to_string().contains("Motion too long!")));
+    }
 
-    println!(
-        "Part one: {}",
-        part_one(Rope::with_knots(2)?, motions.as_slice())
-    );
+    #[test]
+    fn visualizes_rope() -> Result<()> {
+        let input = <Day9 as Solution>::Input::from_text(EXAMPLE)?;
+        let mut out = vec![];
+        let mut animation = Animation::new(&mut out, visual::Mode::Plain).every(usize::MAX);
 
-    println!(
-        "Part two: {}",
-        part_two(Rope::with_knots(10)?, motions.as_slice())
-    );
+        Day9::visualize(&input, &Params::of::<Day9>(), Part::One, &mut animation)?;
+        assert_eq!(animation.frames(), 25);
+        drop(animation);
 
-    Ok(())
+        let out = String::from_utf8(out)?;
+        let last = out.rsplit("Frame 25\n").next().unwrap_or_default();
+        assert_eq!(last, "..##..\n...##.\n.1H##.\n....#.\ns###..\n\n");
+        Ok(())
+    }
 }
@@ -0,0 +1,230 @@
+use std::error;
+use std::fmt;
+use std::io;
+use std::str::FromStr;
+
+pub type ParseResult<T> = Result<T, ParseError>;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    /// Reading the input failed.
+    Io,
+    /// A required line or token is absent.
+    Missing,
+    /// A token is present but malformed.
+    Invalid,
+}
+
+/// Puzzle input parse failure, located by day, one-based line and column, and
+/// the offending token where known.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    kind: ErrorKind,
+    message: String,
+    day: Option<u8>,
+    line: Option<usize>,
+    column: Option<usize>,
+    text: Option<String>,
+    token: Option<String>,
+}
+
+/// Renders like a compiler diagnostic, quoting the offending line:
+///
+/// ```text
+/// error: Invalid tree height!
+///  --> day 8, line 3, column 4
+///   |
+/// 3 | 303a3
+///   |    ^
+/// ```
+impl fmt::Display for ParseError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "error: {}", self.message)?;
+
+        let location = [
+            self.day.map(|day| format!("day {day}")),
+            self.line.map(|line| format!("line {line}")),
+            self.column.map(|column| format!("column {column}")),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+
+        let (Some(line), Some(text)) = (self.line, self.text.as_deref()) else {
+            return match location.is_empty() {
+                true => Ok(()),
+                false => write!(fmt, " ({})", location.join(", ")),
+            };
+        };
+
+        let gutter = " ".repeat(line.to_string().len());
+
+        write!(fmt, "\n{gutter}--> {}", location.join(", "))?;
+        write!(fmt, "\n{gutter} |\n{line} | {text}")?;
+
+        if let Some(column) = self.column {
+            let width = self
+                .token
+                .as_deref()
+                .map(|token| token.chars().count())
+                .unwrap_or_default()
+                .max(1);
+
+            write!(
+                fmt,
+                "\n{gutter} | {}{}",
+                " ".repeat(column - 1),
+                "^".repeat(width)
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl error::Error for ParseError {}
+
+impl From<io::Error> for ParseError {
+    fn from(err: io::Error) -> Self {
+        Self::new(ErrorKind::Io, err.to_string())
+    }
+}
+
+impl ParseError {
+    pub fn new(kind: ErrorKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+            day: None,
+            line: None,
+            column: None,
+            text: None,
+            token: None,
+        }
+    }
+
+    pub fn missing(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Missing, message)
+    }
+
+    pub fn invalid(message: impl Into<String>, token: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Invalid, message).with_token(token)
+    }
+
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    pub fn with_column(mut self, column: usize) -> Self {
+        self.column.get_or_insert(column);
+        self
+    }
+
+    /// Locates the error on the one-based `line` reading `text`, unless an
+    /// inner reader already did. Without a column, the offending token is
+    /// looked up in `text`.
+    pub fn with_line(mut self, line: usize, text: &str) -> Self {
+        if self.line.is_some() {
+            return self;
+        }
+
+        if self.column.is_none() {
+            self.column = self
+                .token
+                .as_deref()
+                .filter(|token| !token.is_empty())
+                .and_then(|token| text.find(token))
+                .map(|idx| text[..idx].chars().count() + 1);
+        }
+
+        self.line = Some(line);
+        self.text = Some(text.to_owned());
+        self
+    }
+
+    pub fn with_day(mut self, day: u8) -> Self {
+        self.day.get_or_insert(day);
+        self
+    }
+
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn day(&self) -> Option<u8> {
+        self.day
+    }
+
+    pub fn line(&self) -> Option<usize> {
+        self.line
+    }
+
+    pub fn column(&self) -> Option<usize> {
+        self.column
+    }
+
+    pub fn token(&self) -> Option<&str> {
+        self.token.as_deref()
+    }
+}
+
+/// A single line describing `err`, where parse errors point at their line
+/// rather than quote it.
+pub fn summary(err: &anyhow::Error) -> String {
+    match err.downcast_ref::<ParseError>() {
+        Some(err) => match (err.line(), err.token()) {
+            (Some(line), Some(token)) => format!("{} ('{token}', line {line})", err.message()),
+            (Some(line), None) => format!("{} (line {line})", err.message()),
+            _ => err.message().to_owned(),
+        },
+        None => format!("{err:#}")
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .to_owned(),
+    }
+}
+
+/// Parses a number, reporting `token` as the offending text on failure.
+pub fn number<T: FromStr>(token: &str) -> ParseResult<T> {
+    token
+        .parse()
+        .map_err(|_| ParseError::invalid("Invalid number!", token))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_located_error() {
+        let err = ParseError::invalid("Invalid number!", "x3")
+            .with_line(2, "U x3")
+            .with_day(9);
+
+        assert_eq!(err.kind(), ErrorKind::Invalid);
+        assert_eq!((err.line(), err.column()), (Some(2), Some(3)));
+        assert_eq!(
+            err.to_string(),
+            "error: Invalid number!\n --> day 9, line 2, column 3\n  |\n2 | U x3\n  |   ^^"
+        );
+    }
+
+    #[test]
+    fn keeps_innermost_location() {
+        let err = ParseError::missing("Missing len!")
+            .with_line(3, "R")
+            .with_line(1, "Monkey 0:");
+
+        assert_eq!(err.line(), Some(3));
+        assert_eq!(
+            err.to_string(),
+            "error: Missing len!\n --> line 3\n  |\n3 | R"
+        );
+    }
+}
@@ -0,0 +1,676 @@
+use std::collections::HashSet;
+use std::ops::RangeInclusive;
+
+use anyhow::{anyhow, Result};
+
+use crate::day10;
+use crate::day14;
+use crate::geometry::Point;
+
+const LOWERCASE: &str = "abcdefghijklmnopqrstuvwxyz";
+const LETTERS: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// Small seedable pseudo-random number generator (SplitMix64), good enough for
+/// test inputs and nothing else.
+#[derive(Clone, Debug)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform below `len`, which must be positive.
+    pub fn below(&mut self, len: usize) -> usize {
+        (self.next_u64() % len as u64) as usize
+    }
+
+    pub fn range(&mut self, range: RangeInclusive<isize>) -> isize {
+        let len = range.end().abs_diff(*range.start()) as u64 + 1;
+        range.start() + (self.next_u64() % len) as isize
+    }
+
+    pub fn bool(&mut self) -> bool {
+        self.next_u64() & 1 == 1
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        (1..items.len()).rev().for_each(|idx| {
+            let other = self.below(idx + 1);
+            items.swap(idx, other);
+        });
+    }
+
+    fn letter(&mut self, letters: &str) -> char {
+        *self.choose(letters.as_bytes()) as char
+    }
+
+    fn word(&mut self, len: RangeInclusive<isize>) -> String {
+        (0..self.range(len))
+            .map(|_| self.letter(LOWERCASE))
+            .collect()
+    }
+}
+
+/// Random but well-formed puzzle input for `day`, from `seed`. `size` roughly
+/// scales the number of lines, records or cells, and is at least one.
+pub fn generate(day: u8, seed: u64, size: usize) -> Result<String> {
+    let rng = &mut Rng::new(seed);
+    let size = size.max(1);
+
+    Ok(match day {
+        1 => calories(rng, size),
+        2 => strategy_guide(rng, size),
+        3 => rucksacks(rng, size),
+        4 => section_pairs(rng, size),
+        5 => crate_drawing(rng, size),
+        6 => datastream(rng, size),
+        7 => terminal_output(rng, size),
+        8 => tree_grid(rng, size),
+        9 => rope_motions(rng, size),
+        10 => cpu_program(rng),
+        11 => monkeys(rng, size),
+        12 => heightmap(rng, size),
+        13 => packet_pairs(rng, size),
+        14 => rock_paths(rng, size),
+        15 => sensors(rng, size)?,
+        _ => return Err(anyhow!("No generator for day {day}!")),
+    })
+}
+
+fn calories(rng: &mut Rng, elves: usize) -> String {
+    // Part two sums the top three elves.
+    (0..elves.max(3))
+        .map(|_| {
+            (0..rng.range(1..=6))
+                .map(|_| format!("{}\n", rng.range(1000..=60000)))
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn strategy_guide(rng: &mut Rng, rounds: usize) -> String {
+    (0..rounds)
+        .map(|_| format!("{} {}\n", rng.letter("ABC"), rng.letter("XYZ")))
+        .collect()
+}
+
+/// Groups of three rucksacks, sharing exactly one badge, whose compartments
+/// share exactly one item.
+fn rucksacks(rng: &mut Rng, size: usize) -> String {
+    let mut out = String::new();
+
+    (0..size.div_ceil(3)).for_each(|_| {
+        let mut letters = LETTERS.chars().collect::<Vec<_>>();
+        rng.shuffle(&mut letters);
+
+        // Every rucksack draws its other items from its own third of the
+        // remaining letters, so the badge is the only item they all share.
+        let badge = letters[0];
+
+        letters[1..].chunks(17).for_each(|pool| {
+            let duplicate = match rng.bool() {
+                true => badge,
+                false => pool[0],
+            };
+
+            let half_len = rng.range(2..=16) as usize;
+            let mut halves = [vec![duplicate], vec![duplicate]];
+
+            if duplicate != badge {
+                halves[rng.below(2)].push(badge);
+            }
+
+            halves
+                .iter_mut()
+                .zip([&pool[1..9], &pool[9..]])
+                .for_each(|(half, only)| {
+                    while half.len() < half_len {
+                        half.push(*rng.choose(only));
+                    }
+
+                    rng.shuffle(half);
+                    out.extend(half.iter());
+                });
+
+            out.push('\n');
+        });
+    });
+
+    out
+}
+
+fn section_pairs(rng: &mut Rng, pairs: usize) -> String {
+    let range = |rng: &mut Rng| {
+        let (start, end) = (rng.range(1..=99), rng.range(1..=99));
+        format!("{}-{}", start.min(end), start.max(end))
+    };
+
+    (0..pairs)
+        .map(|_| format!("{},{}\n", range(rng), range(rng)))
+        .collect()
+}
+
+/// Stacks that each keep at least one crate through every move, so the top
+/// crates always spell out an answer.
+fn crate_drawing(rng: &mut Rng, size: usize) -> String {
+    let mut stacks = (0..rng.range(3..=9))
+        .map(|_| {
+            (0..rng.range(1..=size.clamp(1, 40) as isize))
+                .map(|_| rng.letter(&LETTERS[26..]))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let height = stacks.iter().map(Vec::len).max().unwrap_or_default();
+    let mut out = String::new();
+
+    (0..height).rev().for_each(|row| {
+        let cells = stacks
+            .iter()
+            .map(|stack| match stack.get(row) {
+                Some(item) => format!("[{item}]"),
+                None => "   ".to_owned(),
+            })
+            .collect::<Vec<_>>();
+
+        out += &format!("{}\n", cells.join(" "));
+    });
+
+    let numbers = (1..=stacks.len())
+        .map(|number| format!(" {number} "))
+        .collect::<Vec<_>>();
+
+    out += &format!("{}\n\n", numbers.join(" "));
+
+    for _ in 0..size * 2 {
+        let sources = (0..stacks.len())
+            .filter(|&idx| stacks[idx].len() > 1)
+            .collect::<Vec<_>>();
+
+        if sources.is_empty() {
+            break;
+        }
+
+        let source = *rng.choose(&sources);
+        let destination = (source + 1 + rng.below(stacks.len() - 1)) % stacks.len();
+        let len = rng.range(1..=stacks[source].len() as isize - 1) as usize;
+
+        let keep = stacks[source].len() - len;
+        let moved = stacks[source].split_off(keep);
+        stacks[destination].extend(moved);
+
+        out += &format!("move {len} from {} to {}\n", source + 1, destination + 1);
+    }
+
+    out
+}
+
+/// Random letters with fourteen distinct ones somewhere, so both markers exist.
+fn datastream(rng: &mut Rng, len: usize) -> String {
+    let mut marker = LOWERCASE.chars().collect::<Vec<_>>();
+    rng.shuffle(&mut marker);
+
+    let split = rng.below(len + 1);
+    let mut stream = (0..len).map(|_| rng.letter(LOWERCASE)).collect::<Vec<_>>();
+    stream.splice(split..split, marker.into_iter().take(14));
+
+    stream.into_iter().chain(['\n']).collect()
+}
+
+/// Shell session exploring a random directory tree depth first, whose files
+/// fit on the default disk.
+fn terminal_output(rng: &mut Rng, dirs: usize) -> String {
+    struct Dir {
+        name: String,
+        dirs: Vec<usize>,
+        files: Vec<(usize, String)>,
+    }
+
+    fn explore(dirs: &[Dir], idx: usize, out: &mut String) {
+        let dir = &dirs[idx];
+        out.push_str(&format!("$ cd {}\n$ ls\n", dir.name));
+
+        dir.dirs
+            .iter()
+            .for_each(|&child| out.push_str(&format!("dir {}\n", dirs[child].name)));
+        dir.files
+            .iter()
+            .for_each(|(size, name)| out.push_str(&format!("{size} {name}\n")));
+
+        dir.dirs.iter().for_each(|&child| {
+            explore(dirs, child, out);
+            out.push_str("$ cd ..\n");
+        });
+    }
+
+    let max_file_size = (60_000_000 / (4 * dirs)).clamp(1, 300_000) as isize;
+    let mut names = vec![HashSet::from(["/".to_owned()])];
+    let mut tree = vec![Dir {
+        name: "/".to_owned(),
+        dirs: vec![],
+        files: vec![],
+    }];
+
+    let unique_name = |rng: &mut Rng, names: &mut Vec<HashSet<String>>, parent: usize| loop {
+        let mut name = rng.word(1..=8);
+
+        if rng.bool() {
+            name = format!("{name}.{}", rng.word(1..=3));
+        }
+
+        if names[parent].insert(name.clone()) {
+            return name;
+        }
+    };
+
+    (1..dirs).for_each(|idx| {
+        let parent = rng.below(idx);
+        let name = unique_name(rng, &mut names, parent);
+
+        names.push(HashSet::new());
+        tree[parent].dirs.push(idx);
+        tree.push(Dir {
+            name,
+            dirs: vec![],
+            files: vec![],
+        });
+    });
+
+    (0..tree.len()).for_each(|idx| {
+        (0..rng.range(0..=4)).for_each(|_| {
+            let name = unique_name(rng, &mut names, idx);
+            // Mostly small files, with the odd large one.
+            let size = (rng.range(1..=max_file_size) as usize >> rng.below(12)).max(1);
+            tree[idx].files.push((size, name));
+        });
+    });
+
+    let mut out = String::new();
+    explore(&tree, 0, &mut out);
+    out
+}
+
+fn tree_grid(rng: &mut Rng, size: usize) -> String {
+    let side = size.max(2);
+
+    (0..side)
+        .map(|_| {
+            (0..side)
+                .map(|_| rng.letter("0123456789"))
+                .chain(['\n'])
+                .collect::<String>()
+        })
+        .collect()
+}
+
+fn rope_motions(rng: &mut Rng, motions: usize) -> String {
+    (0..motions)
+        .map(|_| format!("{} {}\n", rng.letter("UDLR"), rng.range(1..=20)))
+        .collect()
+}
+
+/// A program whose CRT shows eight random letters. X only changes between the
+/// two cycles of an `addx`, so the screen is planned two pixels at a time, and
+/// as X starts at 1, the first letter must start with two lit pixels.
+fn cpu_program(rng: &mut Rng) -> String {
+    let starts_lit = day10::FONT
+        .iter()
+        .filter(|(_, glyph)| glyph.starts_with("##"))
+        .collect::<Vec<_>>();
+
+    let mut letters = vec![**rng.choose(&starts_lit)];
+    (1..8).for_each(|_| letters.push(*rng.choose(&day10::FONT)));
+
+    let pixels = (0..6)
+        .flat_map(|row| {
+            letters
+                .iter()
+                .flat_map(move |(_, glyph)| glyph[row * 4..row * 4 + 4].bytes().chain([b'.']))
+        })
+        .map(|pixel| pixel == b'#')
+        .collect::<Vec<_>>();
+
+    // Where X has to be while drawing each pair of pixels.
+    let mut xs = pixels
+        .chunks(2)
+        .zip((0..40).step_by(2).cycle())
+        .map(|(pair, column)| match (pair[0], pair[1]) {
+            (true, true) => column + rng.range(0..=1),
+            (true, false) => column - 1,
+            (false, true) => column + 2,
+            (false, false) => match rng.bool() {
+                true => column - 2 - rng.range(0..=2),
+                false => column + 3 + rng.range(0..=2),
+            },
+        })
+        .collect::<Vec<_>>();
+    xs[0] = 1;
+
+    xs.iter()
+        .zip(xs.iter().skip(1).chain([xs.last().unwrap_or(&1)]))
+        .map(|(x, next)| match next - x {
+            0 => "noop\nnoop\n".to_owned(),
+            delta => format!("addx {delta}\n"),
+        })
+        .collect()
+}
+
+#[derive(Clone, Copy)]
+enum Operation {
+    Add(u64),
+    Multiply(u64),
+    Square,
+}
+
+struct Monkey {
+    items: Vec<u64>,
+    operation: Operation,
+    divisor: u64,
+    targets: [usize; 2],
+}
+
+/// Monkeys whose worry levels stay within 64 bits for the twenty rounds of
+/// part one, which divide but never reduce them.
+fn monkeys(rng: &mut Rng, size: usize) -> String {
+    let len = size.clamp(2, 8);
+
+    let monkeys = loop {
+        let mut divisors = vec![2, 3, 5, 7, 11, 13, 17, 19, 23];
+        rng.shuffle(&mut divisors);
+
+        let monkeys = divisors
+            .into_iter()
+            .take(len)
+            .enumerate()
+            .map(|(idx, divisor)| {
+                let mut targets = (0..len).filter(|&other| other != idx).collect::<Vec<_>>();
+                rng.shuffle(&mut targets);
+
+                Monkey {
+                    items: (0..rng.range(1..=6))
+                        .map(|_| rng.range(50..=99) as u64)
+                        .collect(),
+                    operation: match rng.below(5) {
+                        0 => Operation::Square,
+                        1 | 2 => Operation::Multiply(rng.range(2..=19) as u64),
+                        _ => Operation::Add(rng.range(1..=8) as u64),
+                    },
+                    divisor,
+                    targets: [targets[0], targets[targets.len() - 1]],
+                }
+            })
+            .collect::<Vec<_>>();
+
+        if fits_part_one(&monkeys) {
+            break monkeys;
+        }
+    };
+
+    let mut out = String::new();
+
+    monkeys.iter().enumerate().for_each(|(idx, monkey)| {
+        let items = monkey.items.iter().map(u64::to_string).collect::<Vec<_>>();
+        let operation = match monkey.operation {
+            Operation::Add(operand) => format!("old + {operand}"),
+            Operation::Multiply(operand) => format!("old * {operand}"),
+            Operation::Square => "old * old".to_owned(),
+        };
+
+        if idx > 0 {
+            out.push('\n');
+        }
+
+        out += &format!("Monkey {idx}:\n");
+        out += &format!("  Starting items: {}\n", items.join(", "));
+        out += &format!("  Operation: new = {operation}\n");
+        out += &format!("  Test: divisible by {}\n", monkey.divisor);
+        out += &format!("    If true: throw to monkey {}\n", monkey.targets[0]);
+        out += &format!("    If false: throw to monkey {}\n", monkey.targets[1]);
+    });
+
+    out
+}
+
+fn fits_part_one(monkeys: &[Monkey]) -> bool {
+    let mut items = monkeys
+        .iter()
+        .map(|monkey| monkey.items.clone())
+        .collect::<Vec<_>>();
+
+    for _ in 0..20 {
+        for (idx, monkey) in monkeys.iter().enumerate() {
+            for item in std::mem::take(&mut items[idx]) {
+                let worry = match monkey.operation {
+                    Operation::Add(operand) => item.checked_add(operand),
+                    Operation::Multiply(operand) => item.checked_mul(operand),
+                    Operation::Square => item.checked_mul(item),
+                };
+
+                let Some(worry) = worry.map(|worry| worry / 3) else {
+                    return false;
+                };
+
+                let target = match worry.is_multiple_of(monkey.divisor) {
+                    true => monkey.targets[0],
+                    false => monkey.targets[1],
+                };
+
+                items[target].push(worry);
+            }
+        }
+    }
+
+    true
+}
+
+/// Random elevations around a path from `S` to `E` that never climbs more than
+/// one step at a time, so both parts have an answer.
+fn heightmap(rng: &mut Rng, size: usize) -> String {
+    let (width, height) = ((size * 2).max(20), size.max(8));
+
+    let mut cells = (0..height)
+        .map(|_| {
+            (0..width)
+                .map(|_| rng.letter(LOWERCASE))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let mut steps = [vec![(1, 0); width - 1], vec![(0, 1); height - 1]].concat();
+    rng.shuffle(&mut steps);
+
+    let path_len = steps.len();
+    let (mut x, mut y) = (0, 0);
+
+    steps.into_iter().enumerate().for_each(|(idx, (dx, dy))| {
+        x += dx;
+        y += dy;
+        cells[y][x] = (b'a' + ((idx + 1) * 25 / path_len) as u8) as char;
+    });
+
+    cells[0][0] = 'S';
+    cells[height - 1][width - 1] = 'E';
+
+    cells
+        .into_iter()
+        .map(|row| row.into_iter().chain(['\n']).collect::<String>())
+        .collect()
+}
+
+fn packet_pairs(rng: &mut Rng, pairs: usize) -> String {
+    fn list(rng: &mut Rng, depth: usize) -> String {
+        let items = (0..rng.range(0..=4))
+            .map(|_| match depth < 4 && rng.below(3) == 0 {
+                true => list(rng, depth + 1),
+                false => rng.range(0..=10).to_string(),
+            })
+            .collect::<Vec<_>>();
+
+        format!("[{}]", items.join(","))
+    }
+
+    (0..pairs)
+        .map(|_| format!("{}\n{}\n", list(rng, 1), list(rng, 1)))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Paths of alternating horizontal and vertical lines below the sand source.
+fn rock_paths(rng: &mut Rng, paths: usize) -> String {
+    let spread = (10 + 2 * paths as isize).min(500);
+
+    (0..paths)
+        .map(|_| {
+            let mut point = Point::new(
+                rng.range(500 - spread..=500 + spread),
+                rng.range(2..=spread),
+            );
+            let mut points = vec![point];
+
+            (0..rng.range(1..=4)).for_each(|idx| {
+                let len = rng.range(1..=8) * if rng.bool() { 1 } else { -1 };
+
+                match idx % 2 == 0 {
+                    true => point.x += len,
+                    false => point.y = (point.y + len).max(1),
+                }
+
+                // Stay within the bounds day 14 accepts.
+                point.x = point.x.clamp(0, day14::MAX_COORD);
+                point.y = point.y.min(day14::MAX_COORD);
+
+                if points.last() != Some(&point) {
+                    points.push(point);
+                }
+            });
+
+            let points = points
+                .iter()
+                .map(|point| format!("{},{}", point.x, point.y))
+                .collect::<Vec<_>>();
+
+            format!("{}\n", points.join(" -> "))
+        })
+        .collect()
+}
+
+/// Sensors whose reported beacon is strictly closer than every other beacon,
+/// as the puzzle promises. They spread out with their number, up to the real
+/// inputs' 0..=4000000 square from 25 sensors on.
+fn sensors(rng: &mut Rng, sensors: usize) -> Result<String> {
+    const ATTEMPTS: usize = 1000;
+
+    let span = (160_000 * sensors as isize).min(4_000_000);
+    // Beacons get closer as sensors get more crowded, so that they all fit.
+    let reach = span / 4 / (sensors as isize).isqrt();
+
+    let mut pairs: Vec<(Point, Point)> = vec![];
+    let mut attempts = 0;
+
+    while pairs.len() < sensors {
+        attempts += 1;
+
+        if attempts > ATTEMPTS * sensors {
+            return Err(anyhow!("Could not place {sensors} sensors!"));
+        }
+
+        let sensor = Point::new(rng.range(0..=span), rng.range(0..=span));
+        let offset = Point::new(rng.range(-reach..=reach), rng.range(-reach..=reach));
+        let beacon = sensor + offset;
+        let dist = sensor.manhattan(&beacon);
+
+        let valid = beacon != sensor
+            && pairs.iter().all(|&(other_sensor, other_beacon)| {
+                let other_dist = other_sensor.manhattan(&other_beacon);
+
+                sensor != other_sensor
+                    && sensor != other_beacon
+                    && beacon != other_sensor
+                    && (beacon == other_beacon
+                        || (sensor.manhattan(&other_beacon) > dist
+                            && other_sensor.manhattan(&beacon) > other_dist))
+            });
+
+        if valid {
+            pairs.push((sensor, beacon));
+        }
+    }
+
+    Ok(pairs
+        .iter()
+        .map(|(sensor, beacon)| {
+            format!(
+                "Sensor at x={}, y={}: closest beacon is at x={}, y={}\n",
+                sensor.x, sensor.y, beacon.x, beacon.y
+            )
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::DAYS;
+
+    /// Generates inputs of every day with a generator at `size`, then parses
+    /// and solves both parts of them.
+    fn round_trip(size: usize, seeds: u64) -> Result<()> {
+        // Freshly scaffolded days have no generator yet.
+        DAYS.iter()
+            .filter(|day| generate(day.day(), 0, 1).is_ok())
+            .try_for_each(|day| {
+                (0..seeds).try_for_each(|seed| {
+                    let input = generate(day.day(), seed, size)?;
+
+                    day.solve(input.as_bytes(), &day.parts(), &day.params())?
+                        .into_iter()
+                        .try_for_each(|answer| answer.value.map(|_| ()))
+                        .map_err(|err| {
+                            anyhow!(
+                                "Day {} size {size} seed {seed}: {err:#}\n{input}",
+                                day.day()
+                            )
+                        })
+                })
+            })
+    }
+
+    #[test]
+    fn generated_inputs_are_solvable() -> Result<()> {
+        round_trip(8, 3)
+    }
+
+    #[test]
+    fn tiny_and_large_inputs_are_solvable() -> Result<()> {
+        round_trip(1, 3)?;
+        round_trip(300, 1)
+    }
+
+    #[test]
+    fn places_many_sensors() -> Result<()> {
+        assert_eq!(generate(15, 1, 1000)?.lines().count(), 1000);
+        Ok(())
+    }
+
+    #[test]
+    fn generation_is_deterministic() -> Result<()> {
+        assert_eq!(generate(11, 7, 5)?, generate(11, 7, 5)?);
+        assert_ne!(generate(11, 7, 5)?, generate(11, 8, 5)?);
+        Ok(())
+    }
+}
@@ -0,0 +1,303 @@
+use std::fmt;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+use crate::error::{number, ParseError, ParseResult};
+
+/// Integer point, also used as a displacement vector. `y` grows downwards, as
+/// it does when reading a puzzle input top to bottom.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point {
+    pub x: isize,
+    pub y: isize,
+}
+
+pub type Vec2 = Point;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+/// Inclusive axis-aligned bounding box.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct BoundingBox {
+    pub min: Point,
+    pub max: Point,
+}
+
+impl fmt::Display for Point {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "{},{}", self.x, self.y)
+    }
+}
+
+impl FromStr for Point {
+    type Err = ParseError;
+
+    fn from_str(point: &str) -> ParseResult<Self> {
+        let mut split = point.split(',');
+
+        let x = split
+            .next()
+            .map(str::trim)
+            .ok_or_else(|| ParseError::missing("Missing x-coordinate!"))
+            .and_then(number)?;
+
+        let y = split
+            .next()
+            .map(str::trim)
+            .ok_or_else(|| ParseError::missing("Missing y-coordinate!").with_token(point))
+            .and_then(number)?;
+
+        Ok(Self { x, y })
+    }
+}
+
+impl Add for Point {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Point {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl SubAssign for Point {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl Mul<isize> for Point {
+    type Output = Self;
+
+    fn mul(self, scalar: isize) -> Self {
+        Self::new(self.x * scalar, self.y * scalar)
+    }
+}
+
+impl Neg for Point {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.x, -self.y)
+    }
+}
+
+impl From<(isize, isize)> for Point {
+    fn from((x, y): (isize, isize)) -> Self {
+        Self::new(x, y)
+    }
+}
+
+impl Point {
+    pub const ORIGIN: Self = Self::new(0, 0);
+
+    const OFFSETS_DIAGONAL: [Self; 4] = [
+        Self::new(-1, -1),
+        Self::new(1, -1),
+        Self::new(1, 1),
+        Self::new(-1, 1),
+    ];
+
+    pub const fn new(x: isize, y: isize) -> Self {
+        Self { x, y }
+    }
+
+    pub fn manhattan(&self, other: &Self) -> usize {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    pub fn chebyshev(&self, other: &Self) -> usize {
+        self.x.abs_diff(other.x).max(self.y.abs_diff(other.y))
+    }
+
+    /// Unit step towards this vector's direction along each axis.
+    pub fn signum(&self) -> Self {
+        Self::new(self.x.signum(), self.y.signum())
+    }
+
+    pub fn neighbors(self) -> impl Iterator<Item = Self> {
+        Direction::ALL
+            .into_iter()
+            .map(move |dir| self + dir.as_offset())
+    }
+
+    /// Orthogonal neighbors followed by the four diagonal ones.
+    pub fn neighbors_diagonal(self) -> impl Iterator<Item = Self> {
+        self.neighbors().chain(
+            Self::OFFSETS_DIAGONAL
+                .into_iter()
+                .map(move |offset| self + offset),
+        )
+    }
+}
+
+impl FromStr for Direction {
+    type Err = ParseError;
+
+    fn from_str(str: &str) -> ParseResult<Self> {
+        match str {
+            "U" => Ok(Self::Up),
+            "R" => Ok(Self::Right),
+            "D" => Ok(Self::Down),
+            "L" => Ok(Self::Left),
+            _ => Err(ParseError::invalid("Invalid direction!", str)),
+        }
+    }
+}
+
+impl Direction {
+    /// Clockwise, starting from [`Direction::Up`].
+    pub const ALL: [Self; 4] = [Self::Up, Self::Right, Self::Down, Self::Left];
+
+    pub fn as_offset(&self) -> Vec2 {
+        match self {
+            Self::Up => Vec2::new(0, -1),
+            Self::Right => Vec2::new(1, 0),
+            Self::Down => Vec2::new(0, 1),
+            Self::Left => Vec2::new(-1, 0),
+        }
+    }
+
+    pub fn rotate_right(&self) -> Self {
+        Self::ALL[(*self as usize + 1) % Self::ALL.len()]
+    }
+
+    pub fn rotate_left(&self) -> Self {
+        Self::ALL[(*self as usize + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+
+    pub fn opposite(&self) -> Self {
+        self.rotate_right().rotate_right()
+    }
+}
+
+impl BoundingBox {
+    pub fn new(point: Point) -> Self {
+        Self {
+            min: point,
+            max: point,
+        }
+    }
+
+    pub fn from_points(points: impl IntoIterator<Item = Point>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+
+        Some(points.fold(Self::new(first), |mut bounds, point| {
+            bounds.include(point);
+            bounds
+        }))
+    }
+
+    pub fn include(&mut self, point: Point) {
+        self.min = Point::new(self.min.x.min(point.x), self.min.y.min(point.y));
+        self.max = Point::new(self.max.x.max(point.x), self.max.y.max(point.y));
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        (self.min.x..=self.max.x).contains(&point.x) && (self.min.y..=self.max.y).contains(&point.y)
+    }
+
+    pub fn width(&self) -> usize {
+        self.min.x.abs_diff(self.max.x) + 1
+    }
+
+    pub fn height(&self) -> usize {
+        self.min.y.abs_diff(self.max.y) + 1
+    }
+
+    /// Every point inside the box, row by row.
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let (min, max) = (self.min, self.max);
+        (min.y..=max.y).flat_map(move |y| (min.x..=max.x).map(move |x| Point::new(x, y)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn measures_distances() {
+        let (a, b) = (Point::new(1, -2), Point::new(-3, 5));
+
+        assert_eq!(a.manhattan(&b), 11);
+        assert_eq!(a.chebyshev(&b), 7);
+        assert_eq!((b - a).signum(), Point::new(-1, 1));
+        assert_eq!(a + b, Point::new(-2, 3));
+        assert_eq!(-a * 2, Point::new(-2, 4));
+    }
+
+    #[test]
+    fn rotates_directions() -> ParseResult<()> {
+        assert_eq!(Direction::Up.rotate_right(), Direction::Right);
+        assert_eq!(Direction::Left.rotate_right(), Direction::Up);
+        assert_eq!(Direction::Up.rotate_left(), Direction::Left);
+        assert_eq!(Direction::Down.opposite(), Direction::Up);
+        assert_eq!("L".parse::<Direction>()?.as_offset(), Vec2::new(-1, 0));
+        Ok(())
+    }
+
+    #[test]
+    fn lists_neighbors() {
+        let neighbors = Point::ORIGIN.neighbors_diagonal().collect::<Vec<_>>();
+
+        assert_eq!(
+            neighbors[..4],
+            [(0, -1), (1, 0), (0, 1), (-1, 0)].map(Point::from)
+        );
+        assert_eq!(neighbors.len(), 8);
+        assert!(neighbors
+            .iter()
+            .all(|point| point.chebyshev(&Point::ORIGIN) == 1));
+    }
+
+    #[test]
+    fn grows_bounding_boxes() {
+        let mut bounds = BoundingBox::new(Point::new(2, 3));
+        assert_eq!((bounds.width(), bounds.height()), (1, 1));
+
+        bounds.include(Point::new(0, 4));
+        bounds.include(Point::new(1, 2));
+
+        assert_eq!(bounds.min, Point::new(0, 2));
+        assert_eq!(bounds.max, Point::new(2, 4));
+        assert_eq!((bounds.width(), bounds.height()), (3, 3));
+        assert!(bounds.contains(Point::new(1, 3)));
+        assert!(!bounds.contains(Point::new(3, 3)));
+
+        assert_eq!(
+            BoundingBox::from_points([(0, 0), (1, 1)].map(Point::from))
+                .map(|bounds| bounds.points().collect::<Vec<_>>()),
+            Some([(0, 0), (1, 0), (0, 1), (1, 1)].map(Point::from).to_vec())
+        );
+        assert_eq!(BoundingBox::from_points([]), None);
+    }
+
+    #[test]
+    fn parses_points() -> ParseResult<()> {
+        assert_eq!("498, -4".parse::<Point>()?, Point::new(498, -4));
+        assert_eq!(Point::new(498, -4).to_string(), "498,-4");
+        assert!("498".parse::<Point>().is_err());
+        Ok(())
+    }
+}
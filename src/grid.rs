@@ -0,0 +1,228 @@
+use std::fmt;
+use std::io::BufRead;
+
+use crate::error::{ErrorKind, ParseError, ParseResult};
+use crate::geometry::Point;
+use crate::records::parse_lines;
+
+/// Dense, row-major 2D grid addressed by [`Point`]s, where `x` is the column
+/// and `y` is the row.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.rows().try_for_each(|row| {
+            row.iter()
+                .try_for_each(|cell| write!(fmt, "{cell}"))
+                .and_then(|_| writeln!(fmt))
+        })
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Grid<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_list().entries(self.rows()).finish()
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, cell: T) -> Self {
+        Self {
+            cells: vec![cell; width * height],
+            width,
+            height,
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn from_rows(rows: Vec<Vec<T>>) -> ParseResult<Self> {
+        let width = rows
+            .first()
+            .map(|row| row.len())
+            .ok_or_else(|| ParseError::missing("Empty grid!"))?;
+
+        if rows.iter().any(|row| row.len() != width) {
+            return Err(ParseError::new(ErrorKind::Invalid, "Uneven grid!"));
+        }
+
+        let height = rows.len();
+        let cells = rows.into_iter().flatten().collect();
+
+        Ok(Self {
+            cells,
+            width,
+            height,
+        })
+    }
+
+    /// Parses a character map, one row per line, stopping at the first empty
+    /// line.
+    pub fn from_reader(
+        reader: impl BufRead,
+        parse: impl Fn(char) -> ParseResult<T>,
+    ) -> ParseResult<Self> {
+        let mut width = None;
+
+        parse_lines(reader, |line| {
+            let row = line
+                .chars()
+                .enumerate()
+                .map(|(idx, char)| {
+                    parse(char).map_err(|err| err.with_token(char).with_column(idx + 1))
+                })
+                .collect::<ParseResult<Vec<_>>>()?;
+
+            match *width.get_or_insert(row.len()) == row.len() {
+                true => Ok(row),
+                false => Err(ParseError::invalid("Uneven grid!", line)),
+            }
+        })
+        .and_then(Self::from_rows)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        (0..self.width as isize).contains(&point.x) && (0..self.height as isize).contains(&point.y)
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.index(point).map(|idx| &self.cells[idx])
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        self.index(point).map(|idx| &mut self.cells[idx])
+    }
+
+    /// Replaces the cell at `point`, returning the previous value, or `None`
+    /// if `point` is outside the grid.
+    pub fn set(&mut self, point: Point, cell: T) -> Option<T> {
+        self.get_mut(point)
+            .map(|current| std::mem::replace(current, cell))
+    }
+
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let width = self.width as isize;
+        (0..self.height as isize).flat_map(move |y| (0..width).map(move |x| Point::new(x, y)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.points().zip(self.cells.iter())
+    }
+
+    pub fn position(&self, predicate: impl Fn(&T) -> bool) -> Option<Point> {
+        self.iter()
+            .find(|(_, cell)| predicate(cell))
+            .map(|(point, _)| point)
+    }
+
+    pub fn row(&self, y: usize) -> Option<&[T]> {
+        (y < self.height).then(|| &self.cells[y * self.width..(y + 1) * self.width])
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        self.cells
+            .iter()
+            .skip(x)
+            .step_by(self.width.max(1))
+            .take(if x < self.width { self.height } else { 0 })
+    }
+
+    /// Orthogonally adjacent points of `point` that lie inside the grid.
+    pub fn neighbors(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        point
+            .neighbors()
+            .filter(|&neighbor| self.contains(neighbor))
+    }
+
+    /// Orthogonally and diagonally adjacent points of `point` that lie inside
+    /// the grid.
+    pub fn neighbors_diagonal(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        point
+            .neighbors_diagonal()
+            .filter(|&neighbor| self.contains(neighbor))
+    }
+
+    fn index(&self, point: Point) -> Option<usize> {
+        self.contains(point)
+            .then(|| point.y as usize * self.width + point.x as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid() -> ParseResult<Grid<char>> {
+        Grid::from_reader("abc\ndef\n".as_bytes(), Ok)
+    }
+
+    #[test]
+    fn checks_bounds() -> ParseResult<()> {
+        let mut grid = grid()?;
+
+        assert_eq!(grid.get(Point::new(2, 1)), Some(&'f'));
+        assert_eq!(grid.get(Point::new(-1, 0)), None);
+        assert_eq!(grid.get(Point::new(0, -1)), None);
+        assert_eq!(grid.get(Point::new(3, 0)), None);
+        assert_eq!(grid.get(Point::new(0, 2)), None);
+
+        assert_eq!(grid.get_mut(Point::new(-1, 1)), None);
+        assert_eq!(grid.get_mut(Point::new(1, 2)), None);
+        *grid
+            .get_mut(Point::new(1, 0))
+            .unwrap_or_else(|| unreachable!()) = 'B';
+
+        assert_eq!(grid.set(Point::new(0, 1), 'D'), Some('d'));
+        assert_eq!(grid.set(Point::new(5, 5), 'x'), None);
+        assert_eq!(grid.to_string(), "aBc\nDef\n");
+        Ok(())
+    }
+
+    #[test]
+    fn iterates_rows_columns_and_neighbors() -> ParseResult<()> {
+        let grid = grid()?;
+
+        assert_eq!(
+            grid.rows().collect::<Vec<_>>(),
+            [['a', 'b', 'c'], ['d', 'e', 'f']]
+        );
+        assert_eq!(grid.row(1), Some(&['d', 'e', 'f'][..]));
+        assert_eq!(grid.row(2), None);
+        assert_eq!(grid.column(1).collect::<String>(), "be");
+        assert_eq!(grid.column(3).count(), 0);
+        assert_eq!(grid.position(|&cell| cell == 'e'), Some(Point::new(1, 1)));
+
+        assert_eq!(
+            grid.neighbors(Point::new(0, 0)).collect::<Vec<_>>(),
+            [Point::new(1, 0), Point::new(0, 1)]
+        );
+        assert_eq!(grid.neighbors_diagonal(Point::new(1, 0)).count(), 5);
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_uneven_rows() {
+        let err = Grid::from_reader("abc\nde\n".as_bytes(), Ok).err();
+        assert_eq!(
+            err.map(|err| (err.message().to_owned(), err.line())),
+            Some(("Uneven grid!".to_owned(), Some(2)))
+        );
+    }
+}
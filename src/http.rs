@@ -0,0 +1,337 @@
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+
+const USER_AGENT: &str = concat!("aoc/", env!("CARGO_PKG_VERSION"));
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Minimal HTTP/1.1 client. Plain `http://` URLs are spoken to directly over
+/// TCP, which is what the tests' stand-in servers use, while `https://` URLs
+/// are handed to `curl` for the TLS.
+#[derive(Clone, Debug)]
+pub struct Client {
+    base_url: String,
+    cookie: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub body: String,
+}
+
+enum Method {
+    Get,
+    Post(String),
+}
+
+impl Client {
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_owned(),
+            cookie: None,
+        }
+    }
+
+    pub fn with_cookie(mut self, cookie: String) -> Self {
+        self.cookie = Some(cookie);
+        self
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    pub fn get(&self, path: &str) -> Result<Response> {
+        self.send(Method::Get, path)
+    }
+
+    /// Posts `form` URL-encoded.
+    pub fn post(&self, path: &str, form: &[(&str, &str)]) -> Result<Response> {
+        let body = form
+            .iter()
+            .map(|(name, value)| format!("{}={}", encode(name), encode(value)))
+            .collect::<Vec<_>>()
+            .join("&");
+
+        self.send(Method::Post(body), path)
+    }
+
+    fn send(&self, method: Method, path: &str) -> Result<Response> {
+        let url = format!("{}{path}", self.base_url);
+
+        let raw = match url.strip_prefix("http://") {
+            Some(rest) => self.send_tcp(&method, rest),
+            None if url.starts_with("https://") => self.send_curl(&method, &url),
+            None => Err(anyhow!("Unsupported URL '{url}'!")),
+        }
+        .with_context(|| format!("Requesting {url}"))?;
+
+        parse_response(&raw).with_context(|| format!("Reading response from {url}"))
+    }
+
+    fn send_tcp(&self, method: &Method, url: &str) -> Result<Vec<u8>> {
+        let (host, path) = url.split_at(url.find('/').unwrap_or(url.len()));
+        let path = match path {
+            "" => "/",
+            path => path,
+        };
+
+        let mut request = match method {
+            Method::Get => format!("GET {path} HTTP/1.1\r\n"),
+            Method::Post(_) => format!("POST {path} HTTP/1.1\r\n"),
+        };
+
+        request += &format!("Host: {host}\r\n");
+        request += &self
+            .headers(method)
+            .iter()
+            .map(|header| format!("{header}\r\n"))
+            .collect::<String>();
+        request += "Connection: close\r\n\r\n";
+
+        if let Method::Post(body) = method {
+            request += body;
+        }
+
+        let address = match host.contains(':') {
+            true => host.to_owned(),
+            false => format!("{host}:80"),
+        };
+
+        let mut stream = TcpStream::connect(address)?;
+        stream.set_read_timeout(Some(TIMEOUT))?;
+        stream.write_all(request.as_bytes())?;
+
+        let mut raw = vec![];
+        stream.read_to_end(&mut raw)?;
+        Ok(raw)
+    }
+
+    /// Lets curl do the request, with `--raw` so that the response comes back
+    /// exactly as the server sent it. Headers go through stdin, keeping the
+    /// session cookie out of the process list.
+    fn send_curl(&self, method: &Method, url: &str) -> Result<Vec<u8>> {
+        let mut curl = Command::new("curl");
+        curl.args([
+            "--silent",
+            "--show-error",
+            "--include",
+            "--raw",
+            "--http1.1",
+        ])
+        .args(["--max-time", &TIMEOUT.as_secs().to_string()])
+        .args(["--header", "@-"]);
+
+        if let Method::Post(body) = method {
+            curl.args(["--data-raw", body]);
+        }
+
+        let mut child = curl
+            .arg(url)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .context("Running curl")?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(self.headers(method).join("\n").as_bytes())?;
+        }
+
+        let output = child.wait_with_output()?;
+
+        match output.status.success() {
+            true => Ok(output.stdout),
+            false => Err(anyhow!(
+                "curl failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )),
+        }
+    }
+
+    fn headers(&self, method: &Method) -> Vec<String> {
+        let mut headers = vec![format!("User-Agent: {USER_AGENT}")];
+
+        if let Some(cookie) = &self.cookie {
+            headers.push(format!("Cookie: {cookie}"));
+        }
+
+        if let Method::Post(body) = method {
+            headers.push("Content-Type: application/x-www-form-urlencoded".to_owned());
+            headers.push(format!("Content-Length: {}", body.len()));
+        }
+
+        headers
+    }
+}
+
+fn parse_response(raw: &[u8]) -> Result<Response> {
+    let split = raw
+        .windows(4)
+        .position(|window| window == b"\r\n\r\n")
+        .ok_or_else(|| anyhow!("Incomplete response!"))?;
+
+    let head = String::from_utf8_lossy(&raw[..split]);
+    let body = &raw[split + 4..];
+
+    let mut lines = head.lines();
+
+    let status = lines
+        .next()
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|status| status.parse().ok())
+        .ok_or_else(|| anyhow!("Invalid status line!"))?;
+
+    let header = |name: &str| {
+        head.lines().skip(1).find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            key.trim()
+                .eq_ignore_ascii_case(name)
+                .then(|| value.trim().to_owned())
+        })
+    };
+
+    let body = match (header("Transfer-Encoding"), header("Content-Length")) {
+        (Some(encoding), _) if encoding.eq_ignore_ascii_case("chunked") => dechunk(body)?,
+        (_, Some(len)) => {
+            let len = len
+                .parse()
+                .map_err(|_| anyhow!("Invalid Content-Length!"))?;
+            body.get(..len)
+                .ok_or_else(|| anyhow!("Truncated response!"))?
+                .to_vec()
+        }
+        _ => body.to_vec(),
+    };
+
+    Ok(Response {
+        status,
+        body: String::from_utf8(body)?,
+    })
+}
+
+fn dechunk(mut body: &[u8]) -> Result<Vec<u8>> {
+    let mut out = vec![];
+
+    loop {
+        let line_end = body
+            .windows(2)
+            .position(|window| window == b"\r\n")
+            .ok_or_else(|| anyhow!("Truncated chunk!"))?;
+
+        let size = String::from_utf8_lossy(&body[..line_end]);
+        let size = usize::from_str_radix(size.split(';').next().unwrap_or_default().trim(), 16)
+            .map_err(|_| anyhow!("Invalid chunk size!"))?;
+
+        if size == 0 {
+            return Ok(out);
+        }
+
+        let chunk = body
+            .get(line_end + 2..line_end + 2 + size)
+            .ok_or_else(|| anyhow!("Truncated chunk!"))?;
+
+        out.extend_from_slice(chunk);
+        body = body.get(line_end + 4 + size..).unwrap_or_default();
+    }
+}
+
+fn encode(str: &str) -> String {
+    str.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (byte as char).to_string()
+            }
+            b' ' => "+".to_owned(),
+            byte => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+/// Stand-in HTTP server for tests, answering each connection with the next of
+/// a list of canned responses.
+#[cfg(test)]
+pub(crate) mod mock {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::thread::{self, JoinHandle};
+
+    /// Serves `responses` as `(status, body)` pairs, one connection each.
+    /// Returns the base URL, and a handle yielding the requests received.
+    pub fn serve(responses: Vec<(u16, &'static str)>) -> (String, JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        let handle = thread::spawn(move || {
+            responses
+                .into_iter()
+                .map(|(status, body)| {
+                    let (mut stream, _) = listener.accept().unwrap();
+                    let mut reader = BufReader::new(stream.try_clone().unwrap());
+                    let mut request = String::new();
+
+                    while !request.ends_with("\r\n\r\n") {
+                        reader.read_line(&mut request).unwrap();
+                    }
+
+                    let len = request
+                        .lines()
+                        .find_map(|line| line.strip_prefix("Content-Length: "))
+                        .map_or(0, |len| len.parse().unwrap());
+                    let mut body_in = vec![0; len];
+                    reader.read_exact(&mut body_in).unwrap();
+                    request += &String::from_utf8(body_in).unwrap();
+
+                    write!(
+                        stream,
+                        "HTTP/1.1 {status} OK\r\nContent-Length: {}\r\n\r\n{body}",
+                        body.len()
+                    )
+                    .unwrap();
+
+                    request
+                })
+                .collect()
+        });
+
+        (url, handle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_chunked_responses() -> Result<()> {
+        let raw = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n5\r\nhello\r\n7\r\n, world\r\n0\r\n\r\n";
+
+        assert_eq!(
+            parse_response(raw)?,
+            Response {
+                status: 200,
+                body: "hello, world".to_owned()
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn talks_to_a_server() -> Result<()> {
+        let (url, server) = mock::serve(vec![(200, "ok"), (404, "nope")]);
+        let client = Client::new(&url).with_cookie("session=abc".to_owned());
+
+        assert_eq!(client.get("/input")?.body, "ok");
+        assert_eq!(client.post("/answer", &[("answer", "a b&c")])?.status, 404);
+
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("GET /input HTTP/1.1\r\n"));
+        assert!(requests[0].contains("Cookie: session=abc\r\n"));
+        assert!(requests[1].ends_with("\r\n\r\nanswer=a+b%26c"));
+        Ok(())
+    }
+}
@@ -0,0 +1,114 @@
+use std::env;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+
+/// Path that stands for stdin, as in `--input -`.
+pub const STDIN: &str = "-";
+
+/// Environment variable overriding [`INPUTS_DIR`].
+pub const INPUTS_VAR: &str = "AOC_INPUTS";
+pub const INPUTS_DIR: &str = "inputs";
+pub const EXAMPLES_DIR: &str = "examples";
+
+/// Where puzzle inputs live: `inputs/day07.txt` for a day's input and
+/// `examples/day07-1.txt` for its examples.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Inputs {
+    dir: PathBuf,
+    examples: PathBuf,
+}
+
+impl Default for Inputs {
+    fn default() -> Self {
+        Self::new(INPUTS_DIR)
+    }
+}
+
+impl Inputs {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            examples: EXAMPLES_DIR.into(),
+        }
+    }
+
+    /// The inputs directory named by [`INPUTS_VAR`], or the default one.
+    pub fn from_env() -> Self {
+        env::var_os(INPUTS_VAR).map_or_else(Self::default, Self::new)
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn input(&self, day: u8) -> PathBuf {
+        self.dir.join(format!("day{day:02}.txt"))
+    }
+
+    pub fn example(&self, day: u8, example: usize) -> PathBuf {
+        self.examples.join(format!("day{day:02}-{example}.txt"))
+    }
+
+    /// Opens the given example of `day`, or else its input.
+    pub fn open(&self, day: u8, example: Option<usize>) -> Result<Box<dyn BufRead>> {
+        let path = match example {
+            Some(example) => return open(&self.example(day, example)),
+            None => self.input(day),
+        };
+
+        match path.exists() {
+            true => open(&path),
+            false => Err(anyhow!(
+                "Missing input for day {day}! Save it as {} or pipe it in with --input {STDIN}.",
+                path.display()
+            )),
+        }
+    }
+}
+
+/// Opens `path` for buffered reading, naming it in the error. [`STDIN`] reads
+/// stdin instead.
+pub fn open(path: &Path) -> Result<Box<dyn BufRead>> {
+    if path == Path::new(STDIN) {
+        return Ok(Box::new(io::stdin().lock()));
+    }
+
+    match File::open(path) {
+        Ok(file) => Ok(Box::new(BufReader::new(file))),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            Err(anyhow!("Missing input {}!", path.display()))
+        }
+        Err(err) => Err(anyhow!("{}: {err}", path.display())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_paths() -> Result<()> {
+        let inputs = Inputs::default();
+
+        assert_eq!(inputs.input(7), Path::new("inputs/day07.txt"));
+        assert_eq!(inputs.example(7, 1), Path::new("examples/day07-1.txt"));
+        assert_eq!(Inputs::new("mine").input(12), Path::new("mine/day12.txt"));
+
+        let mut line = String::new();
+        inputs.open(7, Some(1))?.read_line(&mut line)?;
+        assert_eq!(line, "$ cd /\n");
+
+        let err = inputs.open(7, Some(3)).err().map(|err| err.to_string());
+        assert_eq!(err.as_deref(), Some("Missing input examples/day07-3.txt!"));
+
+        let err = Inputs::new("missing").open(7, None).err();
+        assert_eq!(
+            err.map(|err| err.to_string()).as_deref(),
+            Some("Missing input for day 7! Save it as missing/day07.txt or pipe it in with --input -.")
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,418 @@
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::error::{ParseError, ParseResult};
+
+/// Arrays and objects nested deeper than this are rejected rather than risk
+/// overflowing the stack.
+const MAX_DEPTH: usize = 128;
+
+/// Minimal JSON document, written compactly on a single line. Only integer
+/// numbers are supported.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(i128),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Null => write!(fmt, "null"),
+            Self::Bool(bool) => write!(fmt, "{bool}"),
+            Self::Number(number) => write!(fmt, "{number}"),
+            Self::String(string) => write_string(fmt, string),
+            Self::Array(values) => {
+                write!(fmt, "[")?;
+                values.iter().enumerate().try_for_each(|(idx, value)| {
+                    if idx > 0 {
+                        write!(fmt, ",")?;
+                    }
+
+                    write!(fmt, "{value}")
+                })?;
+                write!(fmt, "]")
+            }
+            Self::Object(entries) => {
+                write!(fmt, "{{")?;
+                entries
+                    .iter()
+                    .enumerate()
+                    .try_for_each(|(idx, (key, value))| {
+                        if idx > 0 {
+                            write!(fmt, ",")?;
+                        }
+
+                        write_string(fmt, key)?;
+                        write!(fmt, ":{value}")
+                    })?;
+                write!(fmt, "}}")
+            }
+        }
+    }
+}
+
+impl FromStr for Value {
+    type Err = ParseError;
+
+    fn from_str(text: &str) -> ParseResult<Self> {
+        let mut parser = Parser { text, pos: 0 };
+
+        parser
+            .value(0)
+            .and_then(|value| {
+                parser.skip_whitespace();
+                match parser.peek() {
+                    None => Ok(value),
+                    Some(char) => Err(ParseError::invalid("Trailing characters!", char)),
+                }
+            })
+            .map_err(|err| parser.locate(err))
+    }
+}
+
+impl From<bool> for Value {
+    fn from(bool: bool) -> Self {
+        Self::Bool(bool)
+    }
+}
+
+impl From<u8> for Value {
+    fn from(number: u8) -> Self {
+        Self::Number(number.into())
+    }
+}
+
+impl From<usize> for Value {
+    fn from(number: usize) -> Self {
+        Self::Number(number as i128)
+    }
+}
+
+/// Durations are written as whole nanoseconds.
+impl From<Duration> for Value {
+    fn from(duration: Duration) -> Self {
+        Self::Number(duration.as_nanos() as i128)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(string: &str) -> Self {
+        Self::String(string.to_owned())
+    }
+}
+
+impl From<String> for Value {
+    fn from(string: String) -> Self {
+        Self::String(string)
+    }
+}
+
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(value: Option<T>) -> Self {
+        value.map(Into::into).unwrap_or(Self::Null)
+    }
+}
+
+impl<T: Into<Value>> From<Vec<T>> for Value {
+    fn from(values: Vec<T>) -> Self {
+        Self::Array(values.into_iter().map(Into::into).collect())
+    }
+}
+
+impl Value {
+    pub fn object<K: Into<String>>(entries: impl IntoIterator<Item = (K, Value)>) -> Self {
+        Self::Object(
+            entries
+                .into_iter()
+                .map(|(key, value)| (key.into(), value))
+                .collect(),
+        )
+    }
+}
+
+impl Value {
+    /// Looks up `key` in an object.
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Self::Object(entries) => entries
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    pub fn as_i128(&self) -> Option<i128> {
+        match self {
+            Self::Number(number) => Some(*number),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(string) => Some(string),
+            _ => None,
+        }
+    }
+
+    pub fn as_object(&self) -> Option<&[(String, Value)]> {
+        match self {
+            Self::Object(entries) => Some(entries),
+            _ => None,
+        }
+    }
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn value(&mut self, depth: usize) -> ParseResult<Value> {
+        if depth > MAX_DEPTH {
+            return Err(ParseError::invalid("Nested too deeply!", ""));
+        }
+
+        self.skip_whitespace();
+
+        match self.peek() {
+            Some('{') => self.object(depth),
+            Some('[') => self.array(depth),
+            Some('"') => self.string().map(Value::String),
+            Some('-' | '0'..='9') => self.number(),
+            Some(_) if self.eat("null") => Ok(Value::Null),
+            Some(_) if self.eat("true") => Ok(Value::Bool(true)),
+            Some(_) if self.eat("false") => Ok(Value::Bool(false)),
+            Some(char) => Err(ParseError::invalid("Unexpected character!", char)),
+            None => Err(ParseError::missing("Missing value!")),
+        }
+    }
+
+    fn object(&mut self, depth: usize) -> ParseResult<Value> {
+        self.expect('{')?;
+        let mut entries = vec![];
+
+        self.skip_whitespace();
+        if self.eat("}") {
+            return Ok(Value::Object(entries));
+        }
+
+        loop {
+            self.skip_whitespace();
+            let key = self.string()?;
+            self.skip_whitespace();
+            self.expect(':')?;
+            entries.push((key, self.value(depth + 1)?));
+            self.skip_whitespace();
+
+            match self.eat(",") {
+                true => continue,
+                false => {
+                    self.expect('}')?;
+                    return Ok(Value::Object(entries));
+                }
+            }
+        }
+    }
+
+    fn array(&mut self, depth: usize) -> ParseResult<Value> {
+        self.expect('[')?;
+        let mut values = vec![];
+
+        self.skip_whitespace();
+        if self.eat("]") {
+            return Ok(Value::Array(values));
+        }
+
+        loop {
+            values.push(self.value(depth + 1)?);
+            self.skip_whitespace();
+
+            match self.eat(",") {
+                true => continue,
+                false => {
+                    self.expect(']')?;
+                    return Ok(Value::Array(values));
+                }
+            }
+        }
+    }
+
+    fn string(&mut self) -> ParseResult<String> {
+        self.expect('"')?;
+        let mut string = String::new();
+
+        loop {
+            match self
+                .next()
+                .ok_or_else(|| ParseError::missing("Unterminated string!"))?
+            {
+                '"' => return Ok(string),
+                '\\' => {
+                    let escaped = match self.next() {
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        Some('/') => '/',
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('u') => self.unicode_escape()?,
+                        Some(char) => return Err(ParseError::invalid("Invalid escape!", char)),
+                        None => return Err(ParseError::missing("Unterminated string!")),
+                    };
+                    string.push(escaped);
+                }
+                char => string.push(char),
+            }
+        }
+    }
+
+    /// Reads the four hex digits after `\u`, combining surrogate pairs.
+    fn unicode_escape(&mut self) -> ParseResult<char> {
+        let high = self.hex4()?;
+
+        let code = match (0xd800..0xdc00).contains(&high) && self.eat("\\u") {
+            true => 0x10000 + ((high - 0xd800) << 10) + (self.hex4()?.wrapping_sub(0xdc00) & 0x3ff),
+            false => high,
+        };
+
+        Ok(char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER))
+    }
+
+    fn hex4(&mut self) -> ParseResult<u32> {
+        let digits = self
+            .text
+            .get(self.pos..self.pos + 4)
+            .ok_or_else(|| ParseError::missing("Truncated escape!"))?;
+        let code = u32::from_str_radix(digits, 16)
+            .map_err(|_| ParseError::invalid("Invalid escape!", digits))?;
+
+        self.pos += 4;
+        Ok(code)
+    }
+
+    fn number(&mut self) -> ParseResult<Value> {
+        let start = self.pos;
+        self.eat("-");
+
+        while let Some('0'..='9') = self.peek() {
+            self.pos += 1;
+        }
+
+        let token = &self.text[start..self.pos];
+
+        match self.peek() {
+            Some('.' | 'e' | 'E') => {
+                Err(ParseError::invalid("Only integers are supported!", token))
+            }
+            _ => token
+                .parse()
+                .map(Value::Number)
+                .map_err(|_| ParseError::invalid("Invalid number!", token)),
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.text[self.pos..].chars().next()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let char = self.peek()?;
+        self.pos += char.len_utf8();
+        Some(char)
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        let found = self.text[self.pos..].starts_with(token);
+
+        if found {
+            self.pos += token.len();
+        }
+
+        found
+    }
+
+    fn expect(&mut self, char: char) -> ParseResult<()> {
+        match self.peek() {
+            Some(next) if next == char => {
+                self.pos += char.len_utf8();
+                Ok(())
+            }
+            Some(next) => Err(ParseError::invalid(format!("Expected '{char}'!"), next)),
+            None => Err(ParseError::missing(format!("Expected '{char}'!"))),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.text[self.pos..].trim_start_matches([' ', '\t', '\n', '\r']);
+        self.pos = self.text.len() - rest.len();
+    }
+
+    /// Points `err` at the current position.
+    fn locate(&self, err: ParseError) -> ParseError {
+        let before = &self.text[..self.pos];
+        let line_start = before.rfind('\n').map_or(0, |idx| idx + 1);
+        let line = self.text[line_start..].lines().next().unwrap_or_default();
+
+        err.with_column(before[line_start..].chars().count() + 1)
+            .with_line(before.matches('\n').count() + 1, line)
+    }
+}
+
+fn write_string(fmt: &mut fmt::Formatter<'_>, string: &str) -> fmt::Result {
+    write!(fmt, "\"")?;
+
+    string.chars().try_for_each(|char| match char {
+        '"' => write!(fmt, "\\\""),
+        '\\' => write!(fmt, "\\\\"),
+        '\n' => write!(fmt, "\\n"),
+        '\r' => write!(fmt, "\\r"),
+        '\t' => write!(fmt, "\\t"),
+        char if char.is_control() => write!(fmt, "\\u{:04x}", char as u32),
+        char => write!(fmt, "{char}"),
+    })?;
+
+    write!(fmt, "\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips() -> ParseResult<()> {
+        let text = r#"{"a":[1,-2,null,true],"b":"x\"y\u00e9\ud83d\ude00","c":{}}"#;
+        let value = text.parse::<Value>()?;
+
+        assert_eq!(
+            value.get("b").and_then(Value::as_str),
+            Some("x\"y\u{e9}\u{1f600}")
+        );
+        assert_eq!(
+            value.to_string(),
+            "{\"a\":[1,-2,null,true],\"b\":\"x\\\"y\u{e9}\u{1f600}\",\"c\":{}}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn locates_errors() {
+        let err = "{\n  \"a\": 1.5\n}".parse::<Value>().err();
+
+        assert_eq!(
+            err.map(|err| (err.message().to_owned(), err.line(), err.column())),
+            Some(("Only integers are supported!".to_owned(), Some(2), Some(9)))
+        );
+        assert!("[".repeat(1000).parse::<Value>().is_err());
+    }
+}
@@ -0,0 +1,421 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::error::{number, ParseError, ParseResult};
+use crate::json::Value;
+use crate::runner::Part;
+use crate::PuzzleInput;
+
+/// Private leaderboard, as exported by the site's JSON API.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Leaderboard {
+    pub event: u16,
+    pub members: Vec<Member>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Member {
+    pub id: u64,
+    pub name: Option<String>,
+    /// Score on the global leaderboard, which only the site can compute.
+    pub global_score: u64,
+    /// Unix times of each day's first and second star.
+    pub stars: BTreeMap<u8, [Option<u64>; 2]>,
+}
+
+/// A member's place on the leaderboard, with the local score computed from
+/// star times.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Standing<'a> {
+    pub member: &'a Member,
+    pub local_score: u64,
+    pub stars: usize,
+}
+
+/// How long after the puzzle unlocked a member got each star of a day.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Completion<'a> {
+    pub member: &'a Member,
+    pub part_one: Option<Duration>,
+    pub part_two: Option<Duration>,
+}
+
+/// Standings table, one row per member with a star per day: `*` for both
+/// stars, `+` for just the first.
+pub struct Standings<'a>(pub &'a Leaderboard);
+
+/// Star times of one day, fastest first.
+pub struct DayReport<'a>(pub &'a Leaderboard, pub u8);
+
+impl PuzzleInput for Leaderboard {
+    fn from_reader(mut reader: impl BufRead) -> ParseResult<Self> {
+        let mut text = String::new();
+        reader.read_to_string(&mut text)?;
+
+        let json = text.parse::<Value>()?;
+
+        let event = json
+            .get("event")
+            .and_then(Value::as_str)
+            .ok_or_else(|| ParseError::missing("Missing event!"))
+            .and_then(number)?;
+
+        let members = json
+            .get("members")
+            .and_then(Value::as_object)
+            .ok_or_else(|| ParseError::missing("Missing members!"))?
+            .iter()
+            .map(|(id, member)| Member::from_json(member).map_err(|err| err.with_token(id)))
+            .collect::<ParseResult<_>>()?;
+
+        Ok(Self { event, members })
+    }
+}
+
+impl Member {
+    fn from_json(json: &Value) -> ParseResult<Self> {
+        let id = json
+            .get("id")
+            .and_then(Value::as_i128)
+            .and_then(|id| id.try_into().ok())
+            .ok_or_else(|| ParseError::missing("Missing member id!"))?;
+
+        let name = json.get("name").and_then(Value::as_str).map(str::to_owned);
+
+        let global_score = json
+            .get("global_score")
+            .and_then(Value::as_i128)
+            .and_then(|score| score.try_into().ok())
+            .unwrap_or_default();
+
+        let stars = json
+            .get("completion_day_level")
+            .and_then(Value::as_object)
+            .unwrap_or_default()
+            .iter()
+            .map(|(day, levels)| {
+                let star = |part: &str| {
+                    levels
+                        .get(part)
+                        .and_then(|level| level.get("get_star_ts"))
+                        .and_then(Value::as_i128)
+                        .and_then(|time| time.try_into().ok())
+                };
+
+                Ok((number(day)?, [star("1"), star("2")]))
+            })
+            .collect::<ParseResult<_>>()?;
+
+        Ok(Self {
+            id,
+            name,
+            global_score,
+            stars,
+        })
+    }
+
+    /// The member's name, or how the site shows anonymous members.
+    pub fn display_name(&self) -> String {
+        self.name
+            .clone()
+            .unwrap_or_else(|| format!("(anonymous user #{})", self.id))
+    }
+
+    fn star(&self, day: u8, part: Part) -> Option<u64> {
+        self.stars
+            .get(&day)
+            .and_then(|stars| stars[part.number() as usize - 1])
+    }
+
+    fn star_count(&self) -> usize {
+        self.stars.values().flatten().flatten().count()
+    }
+
+    fn last_star(&self) -> u64 {
+        self.stars
+            .values()
+            .flatten()
+            .flatten()
+            .copied()
+            .max()
+            .unwrap_or(u64::MAX)
+    }
+}
+
+impl Leaderboard {
+    /// Members by local score, where the first member to get a star scores as
+    /// many points as there are members, the second one less, and so on. Ties
+    /// go to whoever got their last star first.
+    pub fn standings(&self) -> Vec<Standing<'_>> {
+        let mut scores = vec![0; self.members.len()];
+
+        self.days().into_iter().for_each(|day| {
+            Part::ALL.iter().for_each(|&part| {
+                let mut times = self
+                    .members
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(idx, member)| member.star(day, part).map(|time| (time, idx)))
+                    .collect::<Vec<_>>();
+                times.sort();
+
+                times.iter().zip(0..).for_each(|(&(_, idx), rank)| {
+                    scores[idx] += self.members.len() as u64 - rank;
+                });
+            })
+        });
+
+        let mut standings = self
+            .members
+            .iter()
+            .zip(scores)
+            .map(|(member, local_score)| Standing {
+                member,
+                local_score,
+                stars: member.star_count(),
+            })
+            .collect::<Vec<_>>();
+
+        standings.sort_by_key(|standing| {
+            (
+                u64::MAX - standing.local_score,
+                standing.member.last_star(),
+                standing.member.id,
+            )
+        });
+
+        standings
+    }
+
+    /// Members with a star on `day`, by their time to both stars, then to the
+    /// first.
+    pub fn completions(&self, day: u8) -> Vec<Completion<'_>> {
+        let unlock = self.unlock_time(day);
+        let since_unlock =
+            |time: Option<u64>| time.map(|time| Duration::from_secs(time.saturating_sub(unlock)));
+
+        let mut completions = self
+            .members
+            .iter()
+            .filter(|member| member.stars.contains_key(&day))
+            .map(|member| Completion {
+                member,
+                part_one: since_unlock(member.star(day, Part::One)),
+                part_two: since_unlock(member.star(day, Part::Two)),
+            })
+            .collect::<Vec<_>>();
+
+        completions.sort_by_key(|completion| {
+            (
+                completion.part_two.unwrap_or(Duration::MAX),
+                completion.part_one,
+            )
+        });
+
+        completions
+    }
+
+    /// Days anyone has a star on.
+    pub fn days(&self) -> Vec<u8> {
+        let mut days = self
+            .members
+            .iter()
+            .flat_map(|member| member.stars.keys().copied())
+            .collect::<Vec<_>>();
+        days.sort();
+        days.dedup();
+        days
+    }
+
+    /// Puzzles unlock at midnight EST, or 05:00 UTC, every day of December.
+    pub fn unlock_time(&self, day: u8) -> u64 {
+        days_from_civil(self.event.into(), 12, day.into()) * 86_400 + 5 * 3600
+    }
+}
+
+impl Completion<'_> {
+    /// Time from the first star to the second.
+    pub fn delta(&self) -> Option<Duration> {
+        Some(self.part_two?.saturating_sub(self.part_one?))
+    }
+}
+
+impl fmt::Display for Standings<'_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let last_day = self.0.days().last().copied().unwrap_or(1);
+
+        writeln!(
+            fmt,
+            "      {:>5}  {:>6}  {:>5}  {}",
+            "Local",
+            "Global",
+            "Stars",
+            (1..=last_day)
+                .map(|day| char::from_digit(day as u32 % 10, 10).unwrap_or(' '))
+                .collect::<String>()
+        )?;
+
+        self.0
+            .standings()
+            .iter()
+            .zip(1..)
+            .try_for_each(|(standing, rank)| {
+                let stars = (1..=last_day)
+                    .map(|day| {
+                        match (
+                            standing.member.star(day, Part::One),
+                            standing.member.star(day, Part::Two),
+                        ) {
+                            (_, Some(_)) => '*',
+                            (Some(_), None) => '+',
+                            (None, None) => ' ',
+                        }
+                    })
+                    .collect::<String>();
+
+                writeln!(
+                    fmt,
+                    "{rank:>4})  {:>5}  {:>6}  {:>5}  {stars}  {}",
+                    standing.local_score,
+                    standing.member.global_score,
+                    standing.stars,
+                    standing.member.display_name()
+                )
+            })
+    }
+}
+
+impl fmt::Display for DayReport<'_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let time = |duration: Option<Duration>| {
+            duration.map_or_else(
+                || "-".to_owned(),
+                |duration| {
+                    let secs = duration.as_secs();
+                    format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+                },
+            )
+        };
+
+        writeln!(
+            fmt,
+            "Day {:<2}  {:>10}  {:>10}  {:>10}",
+            self.1, "Part one", "Part two", "Delta"
+        )?;
+
+        self.0
+            .completions(self.1)
+            .iter()
+            .zip(1..)
+            .try_for_each(|(completion, rank)| {
+                writeln!(
+                    fmt,
+                    "{rank:>4})  {:>10}  {:>10}  {:>10}  {}",
+                    time(completion.part_one),
+                    time(completion.part_two),
+                    time(completion.delta()),
+                    completion.member.display_name()
+                )
+            })
+    }
+}
+
+/// Where a fetched leaderboard is kept, next to the inputs.
+pub fn cache_path(inputs: &Path, id: u64) -> PathBuf {
+    inputs.join(format!("leaderboard-{id}.json"))
+}
+
+/// Days since the Unix epoch of a date in the proleptic Gregorian calendar.
+fn days_from_civil(year: u64, month: u64, day: u64) -> u64 {
+    let year = match month <= 2 {
+        true => year - 1,
+        false => year,
+    };
+    let era = year / 400;
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146_097 + day_of_era - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Day 1 unlocked at 1669870800.
+    const EXPORT: &str = r#"{
+        "owner_id": 1,
+        "event": "2022",
+        "members": {
+            "1": {
+                "id": 1, "name": "Ada", "stars": 3, "local_score": 7, "global_score": 0,
+                "last_star_ts": 1669957500,
+                "completion_day_level": {
+                    "1": {
+                        "1": {"get_star_ts": 1669871100, "star_index": 10},
+                        "2": {"get_star_ts": 1669871400, "star_index": 11}
+                    },
+                    "2": {"1": {"get_star_ts": 1669957500, "star_index": 12}}
+                }
+            },
+            "2": {
+                "id": 2, "name": null, "stars": 2, "local_score": 6, "global_score": 12,
+                "last_star_ts": 1669871000,
+                "completion_day_level": {
+                    "1": {
+                        "1": {"get_star_ts": 1669870900, "star_index": 1},
+                        "2": {"get_star_ts": 1669871000, "star_index": 2}
+                    }
+                }
+            }
+        }
+    }"#;
+
+    #[test]
+    fn computes_standings() -> ParseResult<()> {
+        let leaderboard = Leaderboard::from_text(EXPORT)?;
+
+        let standings = leaderboard
+            .standings()
+            .iter()
+            .map(|standing| {
+                (
+                    standing.member.display_name(),
+                    standing.local_score,
+                    standing.stars,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            standings,
+            [
+                ("(anonymous user #2)".to_owned(), 4, 2),
+                ("Ada".to_owned(), 4, 3),
+            ]
+        );
+
+        let completions = leaderboard.completions(1);
+        assert_eq!(completions[0].member.id, 2);
+        assert_eq!(completions[0].part_one, Some(Duration::from_secs(100)));
+        assert_eq!(completions[1].delta(), Some(Duration::from_secs(300)));
+        Ok(())
+    }
+
+    #[test]
+    fn renders_standings() -> ParseResult<()> {
+        let leaderboard = Leaderboard::from_text(EXPORT)?;
+
+        assert_eq!(
+            Standings(&leaderboard).to_string(),
+            "      Local  Global  Stars  12\n   \
+               1)      4      12      2  *   (anonymous user #2)\n   \
+               2)      4       0      3  *+  Ada\n"
+        );
+        Ok(())
+    }
+}
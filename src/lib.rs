@@ -1 +1,106 @@
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
 
+use anyhow::Result;
+
+use crate::error::ParseResult;
+use crate::params::{Param, Params};
+use crate::runner::Part;
+use crate::visual::Animation;
+
+pub mod batch;
+pub mod bench;
+pub mod day1;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+pub mod error;
+pub mod gen;
+pub mod geometry;
+pub mod grid;
+pub mod http;
+pub mod inputs;
+pub mod json;
+pub mod leaderboard;
+pub mod memory;
+pub mod params;
+pub mod pool;
+pub mod records;
+pub mod runner;
+pub mod scaffold;
+pub mod site;
+pub mod submit;
+pub mod trace;
+pub mod verify;
+pub mod visual;
+pub mod watch;
+
+/// A single day's puzzle: how to parse its input and how to solve both parts.
+pub trait Solution {
+    const DAY: u8;
+
+    type Input: PuzzleInput;
+    type PartOne: fmt::Display + 'static;
+    type PartTwo: fmt::Display + 'static;
+
+    /// Puzzle constants that can be changed from their defaults, e.g. to solve
+    /// the smaller examples. Parts read them from the [`Params`] they are given.
+    const PARAMS: &'static [Param] = &[];
+
+    fn part_one(input: &Self::Input, params: &Params) -> Result<Self::PartOne>;
+
+    /// Days without a part two solution can leave this out and set
+    /// `PartTwo = Unimplemented`.
+    fn part_two(_input: &Self::Input, _params: &Params) -> Result<Self::PartTwo> {
+        Err(Unimplemented.into())
+    }
+
+    /// Animates the simulation behind `part`, for days that have one.
+    fn visualize(
+        _input: &Self::Input,
+        _params: &Params,
+        _part: Part,
+        _animation: &mut Animation<'_>,
+    ) -> Result<()> {
+        Err(Unimplemented.into())
+    }
+}
+
+/// Parsed puzzle input, readable from any buffered reader, a string or a file.
+pub trait PuzzleInput: Sized {
+    fn from_reader(reader: impl BufRead) -> ParseResult<Self>;
+
+    fn from_text(text: &str) -> ParseResult<Self> {
+        Self::from_reader(text.as_bytes())
+    }
+
+    fn from_path(path: impl AsRef<Path>) -> ParseResult<Self> {
+        Self::from_reader(BufReader::new(File::open(path)?))
+    }
+}
+
+/// Answer type and error for a part that has not been solved yet.
+#[derive(Clone, Copy, Debug)]
+pub struct Unimplemented;
+
+impl fmt::Display for Unimplemented {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "Not implemented!")
+    }
+}
+
+impl Error for Unimplemented {}
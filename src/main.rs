@@ -0,0 +1,1219 @@
+use std::env;
+use std::io::{self, BufRead};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::str::FromStr;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use anyhow::{anyhow, Error, Result};
+
+use aoc::batch::{Batch, Matrix};
+use aoc::bench::{self, Bench, Report, Stats};
+use aoc::error::ParseError;
+use aoc::gen;
+use aoc::inputs::{self, Inputs};
+use aoc::json::Value;
+use aoc::leaderboard::{self, DayReport, Leaderboard, Standings};
+use aoc::memory::{self, Counting, Usage};
+use aoc::params::{Config, Params};
+use aoc::pool;
+use aoc::runner::{Day, Part, Solved, DAYS};
+use aoc::scaffold;
+use aoc::site::{Fetched, Site};
+use aoc::submit::{self, Attempt, History, Outcome};
+use aoc::trace;
+use aoc::verify::{self, Answers};
+use aoc::visual::{Animation, Mode};
+use aoc::watch::{self, Diff, Poller, Snapshot};
+use aoc::PuzzleInput;
+
+const USAGE: &str = "\
+Usage:
+    aoc run <day> [--part <1|2>] [--input <file> | --example <n>] [--format <text|json>] [--memory]
+    aoc run all [--part <1|2>] [--input <dir> | --example <n>] [--format <text|json>] [--jobs <n>] [--memory]
+    aoc bench <day|all> [--part <1|2>] [--input <file|dir> | --example <n>] [--runs <n>] [--format <text|json>]
+    aoc verify [--input <dir>] [--answers <file>]
+    aoc batch <dir> [--day <n>] [--jobs <n>]
+    aoc gen <day> [--seed <n>] [--size <n>]
+    aoc vis <day> [--part <1|2>] [--input <file> | --example <n>] [--fps <n>] [--every <n>] [--plain]
+    aoc watch <day> [--part <1|2>] [--interval <ms>]
+    aoc new <day> [--root <dir>]
+    aoc fetch <day|all> [--input <dir>] [--base-url <url>] [--session-file <file>]
+    aoc submit <day> <1|2> [--input <file>] [--history <file>] [--base-url <url>] [--session-file <file>] [--config <file>] [--param <name=value>]
+    aoc leaderboard <file|id> [--day <n>] [--input <dir>] [--base-url <url>] [--session-file <file>]
+    aoc list
+
+Inputs are read from inputs/dayNN.txt, or from the directory in $AOC_INPUTS,
+and examples from examples/dayNN-<n>.txt. A single day reads its input from
+stdin with --input -.
+
+Verify checks the answers to each day's first example against the committed
+examples/answers.txt, with the parameters in examples/config.txt. With --input
+it checks the inputs in that directory against answers.txt instead. Either
+file may be replaced with --answers, with one '<day> <part> <answer>' per line.
+
+Fetch downloads inputs into the inputs directory, skipping any already there.
+Submit solves a part and posts its answer, keeping every attempt in a history
+file, inputs/submissions.txt by default, so that known wrong answers and answers
+during a cooldown are refused without asking the site. Both need the session
+cookie of a logged in browser, from $AOC_SESSION or else the session file,
+~/.config/aoc/session by default. $AOC_BASE_URL overrides the site's URL.
+
+Leaderboard prints the standings of a private leaderboard, or with --day how
+long each member took to get each star of that day. It reads a saved JSON
+export, or fetches the leaderboard with the given id into the inputs directory,
+at most once every 15 minutes.
+
+Batch solves the inputs of a whole team, saved as <dir>/dayNN/<name>.txt, and
+prints each day's answers, failures and timings as a matrix. Answers are
+checked against <dir>/answers/<name>.txt where it exists.
+
+Vis animates days 9, 10, 12 and 14 in the terminal, or dumps every frame as
+plain text with --plain.
+
+Run with --memory also counts what parsing and each part allocate: how many
+allocations, how many bytes in total, and the most bytes live at once.
+
+Watch solves a day's input and examples, then polls them and solves them again
+whenever one changes, showing how the answers changed.
+
+Run, bench, verify, batch, vis, watch and submit also take --param
+[dayNN.]<name>=<value>, which may be repeated, and --config <file> to change
+puzzle constants. aoc list shows them.
+
+$AOC_TRACE traces parsing and solving to stderr, as a comma-separated list of
+levels (error, warn, info, debug, trace or off) for every day or, as
+day<n>=<level>, for a single day. info shows how long each part took, while
+trace also shows the steps inside days 11, 12 and 14.";
+
+const BENCH_RUNS: usize = 10;
+const GEN_SIZE: usize = 10;
+const VIS_FPS: u32 = 30;
+const WATCH_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Counts allocations, but only once `--memory` enables it.
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+/// Which days and parts to run, and where to read their input from: `input`
+/// overrides a single day's input file, and `example` reads examples instead.
+struct Selection {
+    day: Option<Day>,
+    part: Option<Part>,
+    input: Option<PathBuf>,
+    example: Option<usize>,
+    inputs: Inputs,
+    config: Config,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Text,
+    /// One JSON object per day and line, with nothing else on stdout. Running
+    /// all days ends with a line of total times.
+    Json,
+}
+
+enum Command {
+    Run {
+        selection: Selection,
+        format: Format,
+        /// Worker threads to solve all days on.
+        jobs: usize,
+        /// Whether to count allocations.
+        memory: bool,
+    },
+    Bench {
+        selection: Selection,
+        runs: usize,
+        format: Format,
+    },
+    Verify {
+        /// Real inputs to check, or else the first example of every day.
+        inputs: Option<Inputs>,
+        answers: Option<PathBuf>,
+        config: Config,
+    },
+    Batch {
+        dir: PathBuf,
+        days: Vec<Day>,
+        config: Config,
+        jobs: usize,
+    },
+    Gen {
+        day: Day,
+        seed: u64,
+        size: usize,
+    },
+    New {
+        day: u8,
+        /// Crate root to add the day to.
+        root: PathBuf,
+    },
+    Fetch {
+        days: Vec<u8>,
+        inputs: Inputs,
+        site: Site,
+    },
+    Submit {
+        selection: Selection,
+        site: Site,
+        history: PathBuf,
+    },
+    Vis {
+        selection: Selection,
+        mode: Mode,
+        /// Only every nth frame is drawn.
+        every: usize,
+    },
+    Leaderboard {
+        source: Source,
+        day: Option<u8>,
+    },
+    Watch {
+        day: Day,
+        part: Option<Part>,
+        inputs: Inputs,
+        config: Config,
+        /// How often to poll for changes.
+        interval: Duration,
+    },
+    List,
+}
+
+/// Where to read a leaderboard from.
+enum Source {
+    File(PathBuf),
+    Fetch { id: u64, path: PathBuf, site: Site },
+}
+
+impl Command {
+    fn from_args(mut args: impl Iterator<Item = String>) -> Result<Self> {
+        match args.next().as_deref() {
+            Some("list") => Ok(Self::List),
+            Some("gen") => {
+                let day = Day::find(value(&mut args, "day")?.parse()?)?;
+
+                // Without a seed, every run generates a different input.
+                let mut seed = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|time| time.as_nanos() as u64)
+                    .unwrap_or_default();
+                let mut size = GEN_SIZE;
+
+                while let Some(arg) = args.next() {
+                    match arg.as_str() {
+                        "--seed" | "-s" => seed = value(&mut args, "seed")?.parse()?,
+                        "--size" | "-n" => size = value(&mut args, "size")?.parse()?,
+                        arg => return Err(anyhow!("Unknown argument '{arg}'!")),
+                    }
+                }
+
+                Ok(Self::Gen { day, seed, size })
+            }
+            Some("new") => {
+                let day = value(&mut args, "day")?.parse()?;
+                let mut root = PathBuf::from(".");
+
+                while let Some(arg) = args.next() {
+                    match arg.as_str() {
+                        "--root" | "-r" => root = value(&mut args, "crate root")?.into(),
+                        arg => return Err(anyhow!("Unknown argument '{arg}'!")),
+                    }
+                }
+
+                Ok(Self::New { day, root })
+            }
+            Some("fetch") => {
+                let days = match value(&mut args, "day")?.as_str() {
+                    "all" => DAYS.iter().map(Day::day).collect(),
+                    day => vec![day.parse()?],
+                };
+
+                if days.iter().any(|day| !(1..=25).contains(day)) {
+                    return Err(anyhow!("Day must be between 1 and 25!"));
+                }
+
+                let mut input: Option<PathBuf> = None;
+                let mut base_url = None;
+                let mut session_file: Option<PathBuf> = None;
+
+                while let Some(arg) = args.next() {
+                    match arg.as_str() {
+                        "--input" | "-i" => input = Some(value(&mut args, "input path")?.into()),
+                        "--base-url" => base_url = Some(value(&mut args, "base URL")?),
+                        "--session-file" => {
+                            session_file = Some(value(&mut args, "session file")?.into())
+                        }
+                        arg => return Err(anyhow!("Unknown argument '{arg}'!")),
+                    }
+                }
+
+                Ok(Self::Fetch {
+                    days,
+                    inputs: input.map_or_else(Inputs::from_env, Inputs::new),
+                    site: Site::from_env(base_url.as_deref(), session_file.as_deref())?,
+                })
+            }
+            Some("leaderboard") => {
+                let source = value(&mut args, "leaderboard file or id")?;
+
+                let mut day = None;
+                let mut input: Option<PathBuf> = None;
+                let mut base_url = None;
+                let mut session_file: Option<PathBuf> = None;
+
+                while let Some(arg) = args.next() {
+                    match arg.as_str() {
+                        "--day" | "-d" => day = Some(value(&mut args, "day")?.parse()?),
+                        "--input" | "-i" => input = Some(value(&mut args, "input path")?.into()),
+                        "--base-url" => base_url = Some(value(&mut args, "base URL")?),
+                        "--session-file" => {
+                            session_file = Some(value(&mut args, "session file")?.into())
+                        }
+                        arg => return Err(anyhow!("Unknown argument '{arg}'!")),
+                    }
+                }
+
+                if day.is_some_and(|day| !(1..=25).contains(&day)) {
+                    return Err(anyhow!("Day must be between 1 and 25!"));
+                }
+
+                let source = match source.parse() {
+                    Ok(id) => {
+                        let inputs = input.map_or_else(Inputs::from_env, Inputs::new);
+
+                        Source::Fetch {
+                            id,
+                            path: leaderboard::cache_path(inputs.dir(), id),
+                            site: Site::from_env(base_url.as_deref(), session_file.as_deref())?,
+                        }
+                    }
+                    Err(_) => Source::File(source.into()),
+                };
+
+                Ok(Self::Leaderboard { source, day })
+            }
+            Some("submit") => {
+                let day = Day::find(value(&mut args, "day")?.parse()?)?;
+                let part = value(&mut args, "part")?.parse()?;
+
+                if !day.parts().contains(&part) {
+                    return Err(anyhow!("Day {} has no part {part}!", day.day()));
+                }
+
+                let mut input = None;
+                let mut history = None;
+                let mut base_url = None;
+                let mut session_file: Option<PathBuf> = None;
+                let mut config = None;
+                let mut params = vec![];
+
+                while let Some(arg) = args.next() {
+                    match arg.as_str() {
+                        "--input" | "-i" => input = Some(value(&mut args, "input path")?.into()),
+                        "--history" => history = Some(value(&mut args, "history path")?.into()),
+                        "--base-url" => base_url = Some(value(&mut args, "base URL")?),
+                        "--session-file" => {
+                            session_file = Some(value(&mut args, "session file")?.into())
+                        }
+                        "--config" | "-c" => config = Some(value(&mut args, "config path")?.into()),
+                        "--param" | "-P" => params.push(value(&mut args, "parameter")?),
+                        arg => return Err(anyhow!("Unknown argument '{arg}'!")),
+                    }
+                }
+
+                let inputs = Inputs::from_env();
+                let history = history.unwrap_or_else(|| submit::history_path(inputs.dir()));
+                let config = Self::config(config, params, &[day])?;
+
+                Ok(Self::Submit {
+                    selection: Selection {
+                        day: Some(day),
+                        part: Some(part),
+                        input,
+                        example: None,
+                        inputs,
+                        config,
+                    },
+                    site: Site::from_env(base_url.as_deref(), session_file.as_deref())?,
+                    history,
+                })
+            }
+            Some("vis") => {
+                let day = Day::find(value(&mut args, "day")?.parse()?)?;
+
+                let mut part = None;
+                let mut input = None;
+                let mut example = None;
+                let mut fps = VIS_FPS;
+                let mut plain = false;
+                let mut every = 1;
+                let mut config = None;
+                let mut params = vec![];
+
+                while let Some(arg) = args.next() {
+                    match arg.as_str() {
+                        "--part" | "-p" => part = Some(value(&mut args, "part")?.parse()?),
+                        "--input" | "-i" => input = Some(value(&mut args, "input path")?.into()),
+                        "--example" | "-x" => example = Some(value(&mut args, "example")?.parse()?),
+                        "--fps" => fps = value(&mut args, "frame rate")?.parse()?,
+                        "--every" | "-e" => every = value(&mut args, "frame step")?.parse()?,
+                        "--plain" => plain = true,
+                        "--config" | "-c" => config = Some(value(&mut args, "config path")?.into()),
+                        "--param" | "-P" => params.push(value(&mut args, "parameter")?),
+                        arg => return Err(anyhow!("Unknown argument '{arg}'!")),
+                    }
+                }
+
+                if fps == 0 || every == 0 {
+                    return Err(anyhow!("Frame rate and step must be positive!"));
+                }
+
+                let mode = match plain {
+                    true => Mode::Plain,
+                    false => Mode::Terminal { fps },
+                };
+
+                let config = Self::config(config, params, &[day])?;
+                let selection = Selection {
+                    day: Some(day),
+                    part,
+                    input,
+                    example,
+                    inputs: Inputs::from_env(),
+                    config,
+                };
+
+                Ok(Self::Vis {
+                    selection,
+                    mode,
+                    every,
+                })
+            }
+            Some("watch") => {
+                let day = Day::find(value(&mut args, "day")?.parse()?)?;
+
+                let mut part = None;
+                let mut interval = WATCH_INTERVAL;
+                let mut config = None;
+                let mut params = vec![];
+
+                while let Some(arg) = args.next() {
+                    match arg.as_str() {
+                        "--part" | "-p" => part = Some(value(&mut args, "part")?.parse()?),
+                        "--interval" => {
+                            interval = Duration::from_millis(value(&mut args, "interval")?.parse()?)
+                        }
+                        "--config" | "-c" => config = Some(value(&mut args, "config path")?.into()),
+                        "--param" | "-P" => params.push(value(&mut args, "parameter")?),
+                        arg => return Err(anyhow!("Unknown argument '{arg}'!")),
+                    }
+                }
+
+                if interval.is_zero() {
+                    return Err(anyhow!("Interval must be positive!"));
+                }
+
+                let config = Self::config(config, params, &[day])?;
+
+                Ok(Self::Watch {
+                    day,
+                    part,
+                    inputs: Inputs::from_env(),
+                    config,
+                    interval,
+                })
+            }
+            Some(command @ ("run" | "bench")) => {
+                let day = match args.next().as_deref() {
+                    Some("all") => None,
+                    Some(day) => Some(Day::find(day.parse()?)?),
+                    None => return Err(anyhow!("Missing day!")),
+                };
+
+                let mut part = None;
+                let mut input = None;
+                let mut example = None;
+                let mut runs = BENCH_RUNS;
+                let mut jobs = pool::default_workers();
+                let mut memory = false;
+                let mut format = Format::Text;
+                let mut config = None;
+                let mut params = vec![];
+
+                while let Some(arg) = args.next() {
+                    match arg.as_str() {
+                        "--part" | "-p" => part = Some(value(&mut args, "part")?.parse()?),
+                        "--input" | "-i" => input = Some(value(&mut args, "input path")?.into()),
+                        "--example" | "-x" => example = Some(value(&mut args, "example")?.parse()?),
+                        "--format" | "-f" => format = value(&mut args, "format")?.parse()?,
+                        "--config" | "-c" => config = Some(value(&mut args, "config path")?.into()),
+                        "--param" | "-P" => params.push(value(&mut args, "parameter")?),
+                        "--runs" | "-n" if command == "bench" => {
+                            runs = value(&mut args, "run count")?.parse()?;
+
+                            if runs == 0 {
+                                return Err(anyhow!("Run count must be positive!"));
+                            }
+                        }
+                        "--jobs" | "-j" if command == "run" => {
+                            jobs = value(&mut args, "job count")?.parse()?;
+
+                            if jobs == 0 {
+                                return Err(anyhow!("Job count must be positive!"));
+                            }
+                        }
+                        "--memory" | "-m" if command == "run" => memory = true,
+                        arg => return Err(anyhow!("Unknown argument '{arg}'!")),
+                    }
+                }
+
+                // Without a day, the input is a directory to read every day from.
+                let (input, inputs) = match day {
+                    Some(_) => (input, Inputs::from_env()),
+                    None => (None, input.map_or_else(Inputs::from_env, Inputs::new)),
+                };
+
+                let config =
+                    Self::config(config, params, &day.map_or(DAYS.to_vec(), |day| vec![day]))?;
+                let selection = Selection {
+                    day,
+                    part,
+                    input,
+                    example,
+                    inputs,
+                    config,
+                };
+
+                match command {
+                    "bench" => Ok(Self::Bench {
+                        selection,
+                        runs,
+                        format,
+                    }),
+                    _ => Ok(Self::Run {
+                        selection,
+                        format,
+                        jobs,
+                        memory,
+                    }),
+                }
+            }
+            Some("batch") => {
+                let dir = value(&mut args, "input directory")?.into();
+
+                let mut days = DAYS.to_vec();
+                let mut jobs = pool::default_workers();
+                let mut config = None;
+                let mut params = vec![];
+
+                while let Some(arg) = args.next() {
+                    match arg.as_str() {
+                        "--day" | "-d" => {
+                            days = vec![Day::find(value(&mut args, "day")?.parse()?)?]
+                        }
+                        "--jobs" | "-j" => {
+                            jobs = value(&mut args, "job count")?.parse()?;
+
+                            if jobs == 0 {
+                                return Err(anyhow!("Job count must be positive!"));
+                            }
+                        }
+                        "--config" | "-c" => config = Some(value(&mut args, "config path")?.into()),
+                        "--param" | "-P" => params.push(value(&mut args, "parameter")?),
+                        arg => return Err(anyhow!("Unknown argument '{arg}'!")),
+                    }
+                }
+
+                let config = Self::config(config, params, &days)?;
+
+                Ok(Self::Batch {
+                    dir,
+                    days,
+                    config,
+                    jobs,
+                })
+            }
+            Some("verify") => {
+                let mut input: Option<PathBuf> = None;
+                let mut answers = None;
+                let mut config = None;
+                let mut params = vec![];
+
+                while let Some(arg) = args.next() {
+                    match arg.as_str() {
+                        "--input" | "-i" => input = Some(value(&mut args, "input path")?.into()),
+                        "--config" | "-c" => config = Some(value(&mut args, "config path")?.into()),
+                        "--param" | "-P" => params.push(value(&mut args, "parameter")?),
+                        "--answers" | "-a" => {
+                            answers = Some(value(&mut args, "answers path")?.into())
+                        }
+                        arg => return Err(anyhow!("Unknown argument '{arg}'!")),
+                    }
+                }
+
+                let inputs = input.map(Inputs::new);
+                let config = match (config, &inputs) {
+                    (None, None) => Some(verify::EXAMPLE_CONFIG_FILE.into()),
+                    (config, _) => config,
+                };
+                let config = Self::config(config, params, &DAYS)?;
+
+                Ok(Self::Verify {
+                    inputs,
+                    answers,
+                    config,
+                })
+            }
+            Some(command) => Err(anyhow!("Unknown command '{command}'!")),
+            None => Err(anyhow!("Missing command!")),
+        }
+    }
+
+    /// Reads the config file, if any, and applies the command line parameters
+    /// on top, checking that `days` declare them all.
+    fn config(path: Option<PathBuf>, params: Vec<String>, days: &[Day]) -> Result<Config> {
+        let mut config = match path {
+            Some(path) => {
+                Config::from_path(&path).map_err(|err| anyhow!("{}: {err:#}", path.display()))?
+            }
+            None => Config::default(),
+        };
+
+        params.iter().try_for_each(|param| config.push(param))?;
+        config.check(days)?;
+
+        Ok(config)
+    }
+}
+
+impl FromStr for Format {
+    type Err = Error;
+
+    fn from_str(str: &str) -> Result<Self> {
+        match str {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            _ => Err(anyhow!("Invalid format '{str}'!")),
+        }
+    }
+}
+
+impl Selection {
+    fn parts(&self, day: &Day) -> Vec<Part> {
+        match self.part {
+            Some(part) => vec![part],
+            None => day.parts(),
+        }
+    }
+
+    /// Selected days, skipping days that lack the selected part when running
+    /// all of them.
+    fn days(&self) -> Vec<Day> {
+        match self.day {
+            Some(day) => vec![day],
+            None => DAYS
+                .iter()
+                .filter(|day| self.part.is_none_or(|part| day.parts().contains(&part)))
+                .copied()
+                .collect(),
+        }
+    }
+
+    /// Opens the input of every selected day.
+    fn inputs(&self) -> Vec<(Day, Result<Box<dyn BufRead>>)> {
+        match (self.day, &self.input) {
+            (Some(day), Some(path)) => vec![(day, inputs::open(path))],
+            (Some(day), None) => vec![(day, self.inputs.open(day.day(), self.example))],
+            (None, _) => self
+                .days()
+                .into_iter()
+                .map(|day| (day, inputs::open(&self.path(&day))))
+                .collect(),
+        }
+    }
+
+    /// Input file of `day` when running all days.
+    fn path(&self, day: &Day) -> PathBuf {
+        match self.example {
+            Some(example) => self.inputs.example(day.day(), example),
+            None => self.inputs.input(day.day()),
+        }
+    }
+
+    fn solve(&self, day: &Day, reader: Result<impl BufRead>) -> Result<Solved> {
+        reader
+            .and_then(|reader| day.solve_timed(reader, &self.parts(day), &self.config.params(day)))
+    }
+}
+
+fn value(args: &mut impl Iterator<Item = String>, name: &str) -> Result<String> {
+    args.next().ok_or_else(|| anyhow!("Missing {name}!"))
+}
+
+/// Prints a day's error, leaving parse errors to render their own diagnostic.
+fn report(day: &Day, err: &Error) {
+    match err.downcast_ref::<ParseError>() {
+        Some(err) => eprintln!("{err}"),
+        None => eprintln!("Day {}: {err:#}", day.day()),
+    }
+}
+
+fn list() {
+    DAYS.iter().for_each(|day| {
+        let parts = day
+            .parts()
+            .iter()
+            .map(|part| part.to_string())
+            .collect::<Vec<_>>();
+        let params = day
+            .params()
+            .iter()
+            .map(|(name, value)| format!("{name}={value}"))
+            .collect::<Vec<_>>();
+
+        match params.is_empty() {
+            true => println!("Day {}: part {}", day.day(), parts.join(", ")),
+            false => println!(
+                "Day {}: part {} ({})",
+                day.day(),
+                parts.join(", "),
+                params.join(", ")
+            ),
+        }
+    });
+}
+
+fn print(day: &Day, solved: Result<Solved>, header: bool) -> bool {
+    if header {
+        println!("Day {}", day.day());
+    }
+
+    let counted = memory::is_enabled();
+
+    match solved {
+        Ok(solved) => {
+            if counted {
+                println!("Parse: {}", solved.parse_memory);
+            }
+
+            solved
+                .answers
+                .into_iter()
+                .map(|answer| match answer.value {
+                    Ok(value) if counted => {
+                        println!("Part {}: {value} ({})", answer.part, answer.memory);
+                        true
+                    }
+                    Ok(value) => {
+                        println!("Part {}: {value}", answer.part);
+                        true
+                    }
+                    Err(err) => {
+                        eprintln!("Part {}: {err:#}", answer.part);
+                        false
+                    }
+                })
+                .fold(true, |ok, answer_ok| ok & answer_ok)
+        }
+        Err(err) => {
+            report(day, &err);
+            false
+        }
+    }
+}
+
+fn print_json(day: &Day, solved: Result<Solved>) -> bool {
+    let ok = solved
+        .as_ref()
+        .is_ok_and(|solved| solved.answers.iter().all(|answer| answer.value.is_ok()));
+
+    let (parse, parse_memory, answers, err) = match solved {
+        Ok(solved) => (
+            Some(solved.parse),
+            Some(solved.parse_memory),
+            solved.answers,
+            None,
+        ),
+        Err(err) => (None, None, vec![], Some(format!("{err:#}"))),
+    };
+
+    let answers = answers
+        .into_iter()
+        .map(|answer| {
+            let (value, err) = match answer.value {
+                Ok(value) => (Some(value), None),
+                Err(err) => (None, Some(format!("{err:#}"))),
+            };
+
+            Value::object([
+                ("part", answer.part.number().into()),
+                ("answer", value.into()),
+                ("type", answer.type_name.into()),
+                ("duration_ns", answer.elapsed.into()),
+                ("memory", usage_json(Some(answer.memory))),
+                ("error", err.into()),
+            ])
+        })
+        .collect::<Vec<_>>();
+
+    let day = Value::object([
+        ("day", day.day().into()),
+        ("parse_ns", parse.into()),
+        ("parse_memory", usage_json(parse_memory)),
+        ("parts", answers.into()),
+        ("error", err.into()),
+    ]);
+
+    println!("{day}");
+    ok
+}
+
+/// Allocation counts, or null when not counting.
+fn usage_json(usage: Option<Usage>) -> Value {
+    match usage.filter(|_| memory::is_enabled()) {
+        Some(usage) => Value::object([
+            ("allocations", usage.allocations.into()),
+            ("bytes", usage.bytes.into()),
+            ("peak_bytes", usage.peak.into()),
+        ]),
+        None => Value::Null,
+    }
+}
+
+/// Solves every selected day on `jobs` worker threads, then prints them in day
+/// order followed by the wall-clock time against the sum of per-day times.
+fn run_all(selection: &Selection, format: Format, jobs: usize) -> bool {
+    let days = selection.days();
+    let start = Instant::now();
+
+    let results = pool::map(days.clone(), jobs, |day| {
+        let start = Instant::now();
+        let reader = inputs::open(&selection.path(&day));
+        let solved = selection.solve(&day, reader);
+
+        (solved, start.elapsed())
+    });
+
+    let wall = start.elapsed();
+    let mut sum = Duration::ZERO;
+
+    let ok = days
+        .iter()
+        .zip(results)
+        .map(|(day, result)| {
+            let solved = match result {
+                Ok((solved, elapsed)) => {
+                    sum += elapsed;
+                    solved
+                }
+                Err(panic) => Err(anyhow!("Panicked: {panic}")),
+            };
+
+            match format {
+                Format::Text => print(day, solved, true),
+                Format::Json => print_json(day, solved),
+            }
+        })
+        .fold(true, |ok, day_ok| ok & day_ok);
+
+    match format {
+        Format::Text => {
+            println!("Total: {wall:.2?} wall-clock, {sum:.2?} across days (jobs: {jobs})")
+        }
+        Format::Json => println!(
+            "{}",
+            Value::object([
+                ("wall_ns", wall.into()),
+                ("sum_ns", sum.into()),
+                ("jobs", jobs.into()),
+            ])
+        ),
+    }
+
+    ok
+}
+
+fn bench(
+    day: &Day,
+    mut reader: impl BufRead,
+    parts: &[Part],
+    params: &Params,
+    runs: usize,
+) -> Result<Bench> {
+    let mut input = String::new();
+    reader.read_to_string(&mut input)?;
+
+    bench::bench(day, &input, parts, params, runs)
+}
+
+fn bench_json(bench: &Bench) -> Value {
+    let stats = |stats: &Stats| {
+        Value::object([
+            ("min_ns", stats.min.into()),
+            ("median_ns", stats.median.into()),
+            ("max_ns", stats.max.into()),
+        ])
+    };
+
+    let parts = bench
+        .parts
+        .iter()
+        .map(|(part, part_stats)| {
+            Value::object([
+                ("part", part.number().into()),
+                ("duration", stats(part_stats)),
+            ])
+        })
+        .collect::<Vec<_>>();
+
+    Value::object([
+        ("day", bench.day.into()),
+        ("runs", bench.runs.into()),
+        ("parse", stats(&bench.parse)),
+        ("parts", parts.into()),
+        ("error", Value::Null),
+    ])
+}
+
+fn verify(day: &Day, path: &Path, parts: &[Part], params: &Params, answers: &Answers) -> bool {
+    println!("Day {}", day.day());
+
+    match inputs::open(path).and_then(|reader| verify::verify(day, reader, parts, params, answers))
+    {
+        Ok(verdicts) => verdicts
+            .into_iter()
+            .map(|(part, verdict)| match verdict.is_ok() {
+                true => {
+                    println!("Part {part}: {verdict}");
+                    true
+                }
+                false => {
+                    eprintln!("Part {part}: {verdict}");
+                    false
+                }
+            })
+            .fold(true, |ok, part_ok| ok & part_ok),
+        Err(err) => {
+            report(day, &err);
+            false
+        }
+    }
+}
+
+/// Solves the selected part and submits its answer, unless the history already
+/// rules it out.
+fn submit(
+    selection: &Selection,
+    day: &Day,
+    reader: Result<Box<dyn BufRead>>,
+    site: &Site,
+    path: &Path,
+) -> Result<Attempt> {
+    let answer = selection
+        .solve(day, reader)?
+        .answers
+        .into_iter()
+        .next()
+        .ok_or_else(|| anyhow!("Missing answer!"))?;
+
+    let part = answer.part;
+    let answer = answer.value?;
+
+    let mut history = History::load(path)?;
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+
+    history.check(day.day(), part, &answer, now)?;
+
+    let (outcome, cooldown) = site.submit(day.day(), part, &answer)?;
+    let attempt = Attempt {
+        time: now,
+        day: day.day(),
+        part,
+        outcome,
+        cooldown,
+        answer,
+    };
+
+    history.record(path, attempt.clone())?;
+    Ok(attempt)
+}
+
+/// Solves `day` again whenever its input or an example changes, until
+/// interrupted.
+fn watch(day: &Day, parts: &[Part], inputs: &Inputs, params: &Params, interval: Duration) -> ! {
+    let files = watch::files(inputs, day.day());
+    let mut poller = Poller::new(files.clone());
+
+    println!(
+        "Watching {}",
+        files
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+
+    let mut previous = Snapshot::take(day, &files, parts, params);
+    print!("{previous}");
+
+    loop {
+        thread::sleep(interval);
+
+        let changed = poller
+            .poll()
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect::<Vec<_>>();
+
+        if changed.is_empty() {
+            continue;
+        }
+
+        println!("\nChanged {}", changed.join(", "));
+
+        let current = Snapshot::take(day, &files, parts, params);
+        print!("{}", Diff(&previous, &current));
+        previous = current;
+    }
+}
+
+fn main() -> Result<ExitCode> {
+    trace::init()?;
+
+    let command =
+        Command::from_args(env::args().skip(1)).map_err(|err| anyhow!("{err}\n\n{USAGE}"))?;
+
+    if let Command::Run { memory: true, .. } = command {
+        memory::enable();
+    }
+
+    let ok = match command {
+        Command::Gen { day, seed, size } => {
+            print!("{}", gen::generate(day.day(), seed, size)?);
+            true
+        }
+        Command::List => {
+            list();
+            true
+        }
+        Command::New { day, root } => {
+            scaffold::scaffold(&root, day)?
+                .iter()
+                .for_each(|path| println!("Wrote {}", path.display()));
+            true
+        }
+        Command::Fetch { days, inputs, site } => days
+            .into_iter()
+            .map(|day| match site.fetch_input(&inputs, day) {
+                Ok(Fetched::Cached(path)) => {
+                    println!("Day {day}: already saved as {}", path.display());
+                    true
+                }
+                Ok(Fetched::Downloaded(path)) => {
+                    println!("Day {day}: saved as {}", path.display());
+                    true
+                }
+                Err(err) => {
+                    eprintln!("Day {day}: {err:#}");
+                    false
+                }
+            })
+            .fold(true, |ok, day_ok| ok & day_ok),
+        Command::Watch {
+            day,
+            part,
+            inputs,
+            config,
+            interval,
+        } => {
+            let parts = match part {
+                Some(part) => vec![part],
+                None => day.parts(),
+            };
+
+            watch(&day, &parts, &inputs, &config.params(&day), interval)
+        }
+        Command::Batch {
+            dir,
+            days,
+            config,
+            jobs,
+        } => {
+            let runs = Batch::discover(&dir, &days)?.run(&config, jobs);
+            println!("{}", Matrix(&runs));
+
+            runs.iter().all(|run| run.is_ok())
+        }
+        Command::Leaderboard { source, day } => {
+            let path = match source {
+                Source::File(path) => path,
+                Source::Fetch { id, path, site } => match site.fetch_leaderboard(id, &path)? {
+                    Fetched::Cached(path) | Fetched::Downloaded(path) => path,
+                },
+            };
+
+            let leaderboard = Leaderboard::from_path(&path)
+                .map_err(|err| anyhow!("{}: {err}", path.display()))?;
+
+            match day {
+                Some(day) => print!("{}", DayReport(&leaderboard, day)),
+                None => print!("{}", Standings(&leaderboard)),
+            }
+
+            true
+        }
+        Command::Submit {
+            selection,
+            site,
+            history,
+        } => selection
+            .inputs()
+            .into_iter()
+            .map(
+                |(day, reader)| match submit(&selection, &day, reader, &site, &history) {
+                    Ok(attempt) => {
+                        let wait = match attempt.cooldown {
+                            0 => String::new(),
+                            cooldown => format!(", wait {cooldown}s before the next answer"),
+                        };
+
+                        println!(
+                            "Day {} part {}: {} is {}{wait}",
+                            day.day(),
+                            attempt.part,
+                            attempt.answer,
+                            attempt.outcome
+                        );
+                        attempt.outcome == Outcome::Correct
+                    }
+                    Err(err) => {
+                        report(&day, &err);
+                        false
+                    }
+                },
+            )
+            .fold(true, |ok, day_ok| ok & day_ok),
+        Command::Vis {
+            selection,
+            mode,
+            every,
+        } => selection
+            .inputs()
+            .into_iter()
+            .map(|(day, reader)| {
+                let mut animation = Animation::new(io::stdout().lock(), mode).every(every);
+                let part = selection.part.unwrap_or(Part::One);
+
+                match reader.and_then(|reader| {
+                    day.visualize(reader, part, &selection.config.params(&day), &mut animation)
+                }) {
+                    Ok(()) => true,
+                    Err(err) => {
+                        report(&day, &err);
+                        false
+                    }
+                }
+            })
+            .fold(true, |ok, day_ok| ok & day_ok),
+        Command::Run {
+            selection, format, ..
+        } if selection.day.is_some() => selection
+            .inputs()
+            .into_iter()
+            .map(|(day, reader)| {
+                let solved = selection.solve(&day, reader);
+
+                match format {
+                    Format::Text => print(&day, solved, false),
+                    Format::Json => print_json(&day, solved),
+                }
+            })
+            .fold(true, |ok, day_ok| ok & day_ok),
+        Command::Run {
+            selection,
+            format,
+            jobs,
+            ..
+        } => run_all(&selection, format, jobs),
+        Command::Bench {
+            selection,
+            runs,
+            format,
+        } => {
+            let mut ok = true;
+
+            let benches = selection
+                .inputs()
+                .into_iter()
+                .flat_map(|(day, reader)| {
+                    reader
+                        .and_then(|reader| {
+                            bench(
+                                &day,
+                                reader,
+                                &selection.parts(&day),
+                                &selection.config.params(&day),
+                                runs,
+                            )
+                        })
+                        .map_err(|err| {
+                            match format {
+                                Format::Text => report(&day, &err),
+                                Format::Json => println!(
+                                    "{}",
+                                    Value::object([
+                                        ("day", day.day().into()),
+                                        ("error", format!("{err:#}").into()),
+                                    ])
+                                ),
+                            }
+
+                            ok = false;
+                        })
+                })
+                .collect::<Vec<_>>();
+
+            match format {
+                Format::Text => println!("{}", Report(benches.as_slice())),
+                Format::Json => benches
+                    .iter()
+                    .for_each(|bench| println!("{}", bench_json(bench))),
+            }
+
+            ok
+        }
+        Command::Verify {
+            inputs,
+            answers,
+            config,
+        } => {
+            let answers = answers.unwrap_or_else(|| match inputs {
+                Some(_) => verify::ANSWERS_FILE.into(),
+                None => verify::EXAMPLE_ANSWERS_FILE.into(),
+            });
+            let answers = Answers::from_path(&answers)
+                .map_err(|err| anyhow!("{}: {err:#}", answers.display()))?;
+
+            // Examples only check the parts they have answers for, as some
+            // examples only illustrate one part.
+            DAYS.iter()
+                .filter_map(|day| match &inputs {
+                    Some(inputs) => Some((day, inputs.input(day.day()), Part::ALL.to_vec())),
+                    None => match answers.parts(day.day()) {
+                        parts if parts.is_empty() => None,
+                        parts => Some((day, Inputs::default().example(day.day(), 1), parts)),
+                    },
+                })
+                .map(|(day, path, parts)| verify(day, &path, &parts, &config.params(day), &answers))
+                .fold(true, |ok, day_ok| ok & day_ok)
+        }
+    };
+
+    Ok(match ok {
+        true => ExitCode::SUCCESS,
+        false => ExitCode::FAILURE,
+    })
+}
@@ -0,0 +1,241 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// The system allocator, counting what each thread allocates once [`enable`]d.
+/// Binaries opt in by installing it with `#[global_allocator]`.
+pub struct Counting;
+
+/// What a stretch of work allocated. Every reallocation counts as an allocation
+/// of its new size.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Usage {
+    pub allocations: usize,
+    pub bytes: usize,
+    /// Most bytes live at once, on top of those live beforehand.
+    pub peak: usize,
+}
+
+#[derive(Clone, Copy)]
+struct Counts {
+    allocations: usize,
+    bytes: usize,
+    live: isize,
+    peak: isize,
+}
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    // Counting per thread keeps days solved side by side apart. Being plain
+    // data, the counts need neither allocating nor dropping.
+    static COUNTS: Cell<Counts> = const {
+        Cell::new(Counts {
+            allocations: 0,
+            bytes: 0,
+            live: 0,
+            peak: 0,
+        })
+    };
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+
+        if !ptr.is_null() {
+            record(layout.size(), 0);
+        }
+
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+
+        if !ptr.is_null() {
+            record(layout.size(), 0);
+        }
+
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        record(0, layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+
+        if !new_ptr.is_null() {
+            record(new_size, layout.size());
+        }
+
+        new_ptr
+    }
+}
+
+impl fmt::Display for Usage {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let plural = match self.allocations {
+            1 => "",
+            _ => "s",
+        };
+
+        write!(
+            fmt,
+            "{} allocation{plural}, {} allocated, {} peak",
+            self.allocations,
+            Bytes(self.bytes),
+            Bytes(self.peak)
+        )
+    }
+}
+
+/// A byte count in the largest binary unit that keeps it above one.
+struct Bytes(usize);
+
+impl fmt::Display for Bytes {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+        let mut size = self.0 as f64;
+        let mut unit = None;
+
+        for next in UNITS {
+            if size < 1024.0 {
+                break;
+            }
+
+            size /= 1024.0;
+            unit = Some(next);
+        }
+
+        match unit {
+            Some(unit) => write!(fmt, "{size:.1} {unit}"),
+            None => write!(fmt, "{} B", self.0),
+        }
+    }
+}
+
+/// Starts counting allocations, which [`Counting`] otherwise passes straight
+/// through.
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Runs `f`, counting what it allocates on this thread. Measurements may nest.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Usage) {
+    let before = COUNTS.with(|counts| {
+        let before = counts.get();
+        counts.set(Counts {
+            peak: before.live,
+            ..before
+        });
+        before
+    });
+
+    let value = f();
+
+    let after = COUNTS.with(|counts| {
+        let after = counts.get();
+        counts.set(Counts {
+            peak: after.peak.max(before.peak),
+            ..after
+        });
+        after
+    });
+
+    let usage = Usage {
+        allocations: after.allocations - before.allocations,
+        bytes: after.bytes - before.bytes,
+        peak: (after.peak - before.live).max(0) as usize,
+    };
+
+    (value, usage)
+}
+
+fn record(allocated: usize, freed: usize) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+
+    // The counts are gone once the thread has started shutting down.
+    let _ = COUNTS.try_with(|counts| {
+        let mut next = counts.get();
+
+        if allocated > 0 {
+            next.allocations += 1;
+            next.bytes += allocated;
+        }
+
+        next.live += allocated as isize - freed as isize;
+        next.peak = next.peak.max(next.live);
+
+        counts.set(next);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[global_allocator]
+    static ALLOCATOR: Counting = Counting;
+
+    #[test]
+    fn measures_allocations() {
+        enable();
+
+        let (_, outer) = measure(|| {
+            let kept = vec![0u8; 1000];
+
+            let (len, inner) = measure(|| {
+                let mut grown = Vec::<u8>::with_capacity(100);
+                grown.reserve_exact(500);
+                grown.capacity()
+            });
+
+            assert_eq!(len, 500);
+            assert_eq!(
+                inner,
+                Usage {
+                    allocations: 2,
+                    bytes: 600,
+                    peak: 500
+                }
+            );
+
+            kept
+        });
+
+        assert_eq!(
+            outer,
+            Usage {
+                allocations: 3,
+                bytes: 1600,
+                peak: 1500
+            }
+        );
+    }
+
+    #[test]
+    fn formats_usage() {
+        let usage = Usage {
+            allocations: 3,
+            bytes: 1536,
+            peak: 1000,
+        };
+
+        assert_eq!(
+            usage.to_string(),
+            "3 allocations, 1.5 KiB allocated, 1000 B peak"
+        );
+    }
+}
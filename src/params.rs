@@ -0,0 +1,213 @@
+use std::collections::BTreeMap;
+use std::io::BufRead;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Result};
+
+use crate::error::{number, ParseError, ParseResult};
+use crate::runner::Day;
+use crate::{PuzzleInput, Solution};
+
+/// A puzzle constant a [`Solution`] lets callers change, as its name and
+/// default value.
+pub type Param = (&'static str, &'static str);
+
+/// Values of a day's parameters, starting out at their defaults.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Params {
+    values: BTreeMap<&'static str, String>,
+}
+
+/// Parameter overrides, applied in order so later ones win. Overrides without
+/// a day apply to every day declaring the parameter.
+///
+/// Read from a file, overrides are `name = value` lines, scoped to a day by a
+/// preceding `[dayNN]` section. Blank lines and lines starting with `#` are
+/// ignored:
+///
+/// ```text
+/// # Solve the examples rather than the real inputs.
+/// [day15]
+/// y = 10
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Config {
+    overrides: Vec<(Option<u8>, String, String)>,
+}
+
+impl Params {
+    pub fn new(params: &[Param]) -> Self {
+        Self {
+            values: params
+                .iter()
+                .map(|&(name, default)| (name, default.to_owned()))
+                .collect(),
+        }
+    }
+
+    pub fn of<S: Solution>() -> Self {
+        Self::new(S::PARAMS)
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.values.contains_key(name)
+    }
+
+    pub fn set(&mut self, name: &str, value: &str) -> Result<()> {
+        let param = self
+            .values
+            .get_mut(name)
+            .ok_or_else(|| anyhow!("Unknown parameter '{name}'!"))?;
+
+        *param = value.to_owned();
+        Ok(())
+    }
+
+    pub fn with(mut self, name: &str, value: &str) -> Result<Self> {
+        self.set(name, value)?;
+        Ok(self)
+    }
+
+    pub fn get<T: FromStr>(&self, name: &str) -> Result<T> {
+        let value = self
+            .values
+            .get(name)
+            .ok_or_else(|| anyhow!("Unknown parameter '{name}'!"))?;
+
+        value
+            .parse()
+            .map_err(|_| anyhow!("Invalid value '{value}' for parameter '{name}'!"))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.values
+            .iter()
+            .map(|(name, value)| (*name, value.as_str()))
+    }
+}
+
+impl PuzzleInput for Config {
+    fn from_reader(reader: impl BufRead) -> ParseResult<Self> {
+        let mut config = Self::default();
+        let mut day = None;
+
+        for (line, idx) in reader.lines().zip(1..) {
+            let line = line?;
+            let text = line.trim();
+
+            if text.is_empty() || text.starts_with('#') {
+                continue;
+            }
+
+            let parsed = match text.strip_prefix('[') {
+                Some(section) => section
+                    .strip_suffix(']')
+                    .ok_or_else(|| ParseError::missing("Missing ']'!").with_token(text))
+                    .and_then(|section| parse_day(section.trim()))
+                    .map(|section| day = Some(section)),
+                None => parse_assignment(text)
+                    .map(|(name, value)| config.overrides.push((day, name, value))),
+            };
+
+            parsed.map_err(|err| err.with_line(idx, &line))?;
+        }
+
+        Ok(config)
+    }
+}
+
+impl Config {
+    /// Adds an override given as `[dayNN.]name=value`, e.g. from the command
+    /// line.
+    pub fn push(&mut self, param: &str) -> Result<()> {
+        let (name, value) = parse_assignment(param).map_err(|err| anyhow!("{}", err.message()))?;
+
+        let (day, name) = match name.split_once('.') {
+            Some((day, name)) => (
+                Some(parse_day(day).map_err(|err| anyhow!("{}", err.message()))?),
+                name.to_owned(),
+            ),
+            None => (None, name),
+        };
+
+        self.overrides.push((day, name, value));
+        Ok(())
+    }
+
+    /// Checks that every override names a parameter of one of `days`, so that
+    /// misspelt names do not go unnoticed.
+    pub fn check(&self, days: &[Day]) -> Result<()> {
+        self.overrides.iter().try_for_each(|(day, name, _)| {
+            let declared = days
+                .iter()
+                .filter(|candidate| day.is_none_or(|day| candidate.day() == day))
+                .any(|candidate| candidate.params().contains(name));
+
+            match (declared, day) {
+                (true, _) => Ok(()),
+                (false, Some(day)) => Err(anyhow!("Unknown parameter '{name}' for day {day}!")),
+                (false, None) => Err(anyhow!("Unknown parameter '{name}'!")),
+            }
+        })
+    }
+
+    /// Defaults of `day`'s parameters with the overrides for it applied.
+    pub fn params(&self, day: &Day) -> Params {
+        let mut params = day.params();
+
+        self.overrides
+            .iter()
+            .filter(|(scope, _, _)| scope.is_none_or(|scope| scope == day.day()))
+            .for_each(|(_, name, value)| {
+                if let Some(param) = params.values.get_mut(name.as_str()) {
+                    param.clone_from(value);
+                }
+            });
+
+        params
+    }
+}
+
+fn parse_day(day: &str) -> ParseResult<u8> {
+    day.strip_prefix("day")
+        .ok_or_else(|| ParseError::invalid("Expected 'dayNN'!", day))
+        .and_then(number)
+}
+
+fn parse_assignment(text: &str) -> ParseResult<(String, String)> {
+    let (name, value) = text
+        .split_once('=')
+        .ok_or_else(|| ParseError::missing("Expected 'name = value'!").with_token(text))?;
+
+    match name.trim() {
+        "" => Err(ParseError::missing("Missing parameter name!").with_token(text)),
+        name => Ok((name.to_owned(), value.trim().to_owned())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::DAYS;
+
+    const CONFIG: &str = "# Examples\n[day15]\ny = 10\n\n[day14]\nsource = 500,0\n";
+
+    #[test]
+    fn applies_overrides_in_order() -> Result<()> {
+        let mut config = Config::from_text(CONFIG)?;
+        config.push("y=20")?;
+
+        let day = Day::find(15)?;
+        config.check(&DAYS)?;
+
+        assert_eq!(config.params(&day).get::<isize>("y")?, 20);
+        assert!(config.check(&[Day::find(1)?]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn locates_config_errors() {
+        let err = Config::from_text("[day15]\ny 10\n").unwrap_err();
+        assert_eq!(err.line(), Some(2));
+    }
+}
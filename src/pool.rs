@@ -0,0 +1,83 @@
+use std::any::Any;
+use std::num::NonZeroUsize;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{mpsc, Mutex, PoisonError};
+use std::thread;
+
+/// Number of workers to use when none is given: one per available core.
+pub fn default_workers() -> usize {
+    thread::available_parallelism()
+        .map(NonZeroUsize::get)
+        .unwrap_or(1)
+}
+
+/// Maps `f` over `items` on up to `workers` threads, returning the results in
+/// the order of `items`. A panic in `f` is caught and returned as that item's
+/// panic message, leaving the other items unaffected.
+pub fn map<T, R, F>(items: Vec<T>, workers: usize, f: F) -> Vec<Result<R, String>>
+where
+    T: Send,
+    R: Send,
+    F: Fn(T) -> R + Sync,
+{
+    let len = items.len();
+    let queue = Mutex::new(items.into_iter().enumerate());
+    let (sender, receiver) = mpsc::channel();
+
+    // The lock is only held while taking the next item, so no panic can
+    // poison it.
+    let next = || queue.lock().unwrap_or_else(PoisonError::into_inner).next();
+
+    let (f, next) = (&f, &next);
+
+    thread::scope(|scope| {
+        (0..workers.clamp(1, len.max(1))).for_each(|_| {
+            let sender = sender.clone();
+
+            scope.spawn(move || {
+                while let Some((idx, item)) = next() {
+                    let result = panic::catch_unwind(AssertUnwindSafe(|| f(item)));
+                    let _ = sender.send((idx, result.map_err(message)));
+                }
+            });
+        });
+    });
+
+    drop(sender);
+
+    let mut results = (0..len).map(|_| None).collect::<Vec<_>>();
+    receiver
+        .into_iter()
+        .for_each(|(idx, result)| results[idx] = Some(result));
+
+    results.into_iter().flatten().collect()
+}
+
+/// The message a caught panic was raised with.
+pub fn message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => payload
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .unwrap_or_else(|| "Unknown panic!".to_owned()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_order_and_isolates_panics() {
+        let results = map((0..8).collect(), 3, |n: u32| match n {
+            5 => panic!("Five!"),
+            n => n * 2,
+        });
+
+        assert_eq!(results.len(), 8);
+        assert_eq!(results[4], Ok(8));
+        assert_eq!(results[5], Err("Five!".to_owned()));
+        assert_eq!(results[7], Ok(14));
+    }
+}
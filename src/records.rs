@@ -0,0 +1,177 @@
+use std::io::{BufRead, Lines};
+
+use crate::error::ParseResult;
+
+/// A group of consecutive non-blank lines, as found between blank lines in a
+/// puzzle input.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Record {
+    /// One-based line number of the first line in the record.
+    pub line: usize,
+    pub lines: Vec<String>,
+}
+
+/// Iterator over the blank-line separated [`Record`]s of a reader. Runs of
+/// blank lines count as a single separator, and the last record does not need
+/// a trailing blank line.
+pub struct Records<R> {
+    lines: Lines<R>,
+    line: usize,
+}
+
+impl Record {
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// Lines paired with their one-based line numbers.
+    pub fn numbered(&self) -> impl Iterator<Item = (usize, &str)> {
+        (self.line..).zip(self.lines.iter().map(|line| line.as_str()))
+    }
+
+    /// Parses each line with `parse`, locating any error on its line.
+    pub fn parse_lines<T>(
+        &self,
+        mut parse: impl FnMut(&str) -> ParseResult<T>,
+    ) -> ParseResult<Vec<T>> {
+        self.numbered()
+            .map(|(idx, line)| parse(line).map_err(|err| err.with_line(idx, line)))
+            .collect()
+    }
+}
+
+impl<R: BufRead> Records<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            lines: reader.lines(),
+            line: 0,
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for Records<R> {
+    type Item = ParseResult<Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut record = Record {
+            line: 0,
+            lines: vec![],
+        };
+
+        for line in self.lines.by_ref() {
+            self.line += 1;
+
+            let line = match line {
+                Ok(line) => line,
+                Err(err) => return Some(Err(err.into())),
+            };
+
+            // `lines` already strips "\r\n", but a stray '\r' is still blank.
+            let line = line.trim_end_matches('\r');
+
+            match (line.trim().is_empty(), record.is_empty()) {
+                (true, true) => continue,
+                (true, false) => break,
+                (false, _) => {
+                    if record.is_empty() {
+                        record.line = self.line;
+                    }
+
+                    record.lines.push(line.to_owned());
+                }
+            }
+        }
+
+        (!record.is_empty()).then_some(Ok(record))
+    }
+}
+
+pub fn records<R: BufRead>(reader: R) -> Records<R> {
+    Records::new(reader)
+}
+
+/// Parses each line up to the first blank one with `parse`, locating any error
+/// on its line.
+pub fn parse_lines<T>(
+    reader: impl BufRead,
+    mut parse: impl FnMut(&str) -> ParseResult<T>,
+) -> ParseResult<Vec<T>> {
+    reader
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| (idx + 1, line))
+        .take_while(|(_, line)| line.as_ref().map(|line| !line.is_empty()).unwrap_or(true))
+        .map(|(idx, line)| {
+            let line = line?;
+            parse(&line).map_err(|err| err.with_line(idx, &line))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::number;
+
+    fn read(input: &str) -> ParseResult<Vec<Record>> {
+        records(input.as_bytes()).collect()
+    }
+
+    #[test]
+    fn splits_on_blank_lines() -> ParseResult<()> {
+        let records = read("\n\na\nb\n\n\n  \nc\n")?;
+
+        assert_eq!(
+            records,
+            [
+                Record {
+                    line: 3,
+                    lines: vec!["a".to_owned(), "b".to_owned()],
+                },
+                Record {
+                    line: 8,
+                    lines: vec!["c".to_owned()],
+                },
+            ]
+        );
+        assert_eq!(
+            records[0].numbered().collect::<Vec<_>>(),
+            [(3, "a"), (4, "b")]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn reads_crlf_and_a_missing_last_newline() -> ParseResult<()> {
+        let records = read("1\r\n2\r\n\r\n3")?;
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].lines, ["1", "2"]);
+        assert_eq!(
+            (records[1].line, records[1].lines.as_slice()),
+            (4, &["3".to_owned()][..])
+        );
+        assert!(read("")?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn locates_errors_on_their_line() -> ParseResult<()> {
+        let record = read("\n1\nx\n")?.remove(0);
+        let err = record.parse_lines(number::<u8>).err();
+
+        assert_eq!(
+            err.map(|err| (err.line(), err.token().map(str::to_owned))),
+            Some((Some(3), Some("x".to_owned())))
+        );
+
+        let err = parse_lines("1\n2\nx\n\ny\n".as_bytes(), number::<u8>).err();
+        assert_eq!(err.and_then(|err| err.line()), Some(3));
+        assert_eq!(parse_lines("1\n\nx\n".as_bytes(), number::<u8>)?, [1]);
+        Ok(())
+    }
+}
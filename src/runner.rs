@@ -0,0 +1,341 @@
+use std::any::{self, TypeId};
+use std::fmt;
+use std::io::BufRead;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Error, Result};
+
+use crate::day1::Day1;
+use crate::day10::Day10;
+use crate::day11::Day11;
+use crate::day12::Day12;
+use crate::day13::Day13;
+use crate::day14::Day14;
+use crate::day15::Day15;
+use crate::day2::Day2;
+use crate::day3::Day3;
+use crate::day4::Day4;
+use crate::day5::Day5;
+use crate::day6::Day6;
+use crate::day7::Day7;
+use crate::day8::Day8;
+use crate::day9::Day9;
+use crate::memory::{self, Usage};
+use crate::params::{Param, Params};
+use crate::trace::{self, Level};
+use crate::visual::Animation;
+use crate::{PuzzleInput, Solution, Unimplemented};
+
+pub const DAYS: [Day; 15] = [
+    Day::new::<Day1>(),
+    Day::new::<Day2>(),
+    Day::new::<Day3>(),
+    Day::new::<Day4>(),
+    Day::new::<Day5>(),
+    Day::new::<Day6>(),
+    Day::new::<Day7>(),
+    Day::new::<Day8>(),
+    Day::new::<Day9>(),
+    Day::new::<Day10>(),
+    Day::new::<Day11>(),
+    Day::new::<Day12>(),
+    Day::new::<Day13>(),
+    Day::new::<Day14>(),
+    Day::new::<Day15>(),
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+#[derive(Debug)]
+pub struct Answer {
+    pub part: Part,
+    pub value: Result<String>,
+    /// Name of the answer's type, e.g. `usize`.
+    pub type_name: &'static str,
+    pub elapsed: Duration,
+    /// What solving allocated, all zero unless [`memory`] counting is on.
+    pub memory: Usage,
+}
+
+/// Answers along with how long parsing the input took, and what it allocated.
+#[derive(Debug)]
+pub struct Solved {
+    pub parse: Duration,
+    pub parse_memory: Usage,
+    pub answers: Vec<Answer>,
+}
+
+/// Type-erased handle to a [`Solution`], so days can be picked at runtime.
+#[derive(Clone, Copy)]
+pub struct Day {
+    day: u8,
+    parts: fn() -> Vec<Part>,
+    params: &'static [Param],
+    solve: fn(&mut dyn BufRead, &[Part], &Params) -> Result<Solved>,
+    visualize: fn(&mut dyn BufRead, Part, &Params, &mut Animation<'_>) -> Result<()>,
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::One => write!(fmt, "one"),
+            Self::Two => write!(fmt, "two"),
+        }
+    }
+}
+
+impl FromStr for Part {
+    type Err = Error;
+
+    fn from_str(str: &str) -> Result<Self> {
+        match str {
+            "1" | "one" => Ok(Self::One),
+            "2" | "two" => Ok(Self::Two),
+            _ => Err(anyhow!("Invalid part '{str}'!")),
+        }
+    }
+}
+
+impl Part {
+    pub const ALL: [Self; 2] = [Self::One, Self::Two];
+
+    pub fn number(&self) -> u8 {
+        match self {
+            Self::One => 1,
+            Self::Two => 2,
+        }
+    }
+}
+
+impl fmt::Debug for Day {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("Day")
+            .field("day", &self.day)
+            .field("parts", &self.parts())
+            .field("params", &self.params)
+            .finish()
+    }
+}
+
+impl Day {
+    pub const fn new<S: Solution>() -> Self {
+        Self {
+            day: S::DAY,
+            parts: parts::<S>,
+            params: S::PARAMS,
+            solve: solve::<S>,
+            visualize: visualize::<S>,
+        }
+    }
+
+    pub fn find(day: u8) -> Result<Self> {
+        DAYS.iter()
+            .find(|solution| solution.day == day)
+            .copied()
+            .ok_or_else(|| anyhow!("Day {day} is not implemented!"))
+    }
+
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+
+    pub fn parts(&self) -> Vec<Part> {
+        (self.parts)()
+    }
+
+    /// The day's parameters at their defaults.
+    pub fn params(&self) -> Params {
+        Params::new(self.params)
+    }
+
+    /// Parses `reader` and solves the requested `parts`. A parse failure fails
+    /// the whole day, whereas each part's answer carries its own error.
+    pub fn solve(
+        &self,
+        reader: impl BufRead,
+        parts: &[Part],
+        params: &Params,
+    ) -> Result<Vec<Answer>> {
+        self.solve_timed(reader, parts, params)
+            .map(|solved| solved.answers)
+    }
+
+    pub fn solve_timed(
+        &self,
+        mut reader: impl BufRead,
+        parts: &[Part],
+        params: &Params,
+    ) -> Result<Solved> {
+        let implemented = self.parts();
+
+        if let Some(part) = parts.iter().find(|&part| !implemented.contains(part)) {
+            return Err(anyhow!("Part {part} is not implemented!"));
+        }
+
+        (self.solve)(&mut reader, parts, params)
+    }
+
+    /// Parses `reader` and animates the simulation behind `part`.
+    pub fn visualize(
+        &self,
+        mut reader: impl BufRead,
+        part: Part,
+        params: &Params,
+        animation: &mut Animation<'_>,
+    ) -> Result<()> {
+        (self.visualize)(&mut reader, part, params, animation)
+    }
+}
+
+fn type_name<T>() -> &'static str {
+    let name = any::type_name::<T>();
+    name.rsplit("::").next().unwrap_or(name)
+}
+
+fn parts<S: Solution>() -> Vec<Part> {
+    match TypeId::of::<S::PartTwo>() == TypeId::of::<Unimplemented>() {
+        true => vec![Part::One],
+        false => Part::ALL.to_vec(),
+    }
+}
+
+fn solve<S: Solution>(reader: &mut dyn BufRead, parts: &[Part], params: &Params) -> Result<Solved> {
+    let start = Instant::now();
+    let (input, parse_memory) = memory::measure(|| parse::<S>(reader));
+    let input = input?;
+    let parse = start.elapsed();
+
+    let answers = parts
+        .iter()
+        .map(|&part| {
+            let _span = trace::span(
+                S::DAY,
+                Level::Info,
+                match part {
+                    Part::One => "part one",
+                    Part::Two => "part two",
+                },
+            );
+
+            let start = Instant::now();
+            let ((value, type_name), memory) = memory::measure(|| match part {
+                Part::One => (
+                    S::part_one(&input, params).map(|answer| answer.to_string()),
+                    type_name::<S::PartOne>(),
+                ),
+                Part::Two => (
+                    S::part_two(&input, params).map(|answer| answer.to_string()),
+                    type_name::<S::PartTwo>(),
+                ),
+            });
+
+            let elapsed = start.elapsed();
+
+            if let Err(err) = &value {
+                trace::event(Level::Error, "failed", &[("error", err)]);
+            }
+
+            Answer {
+                part,
+                value,
+                type_name,
+                elapsed,
+                memory,
+            }
+        })
+        .collect();
+
+    Ok(Solved {
+        parse,
+        parse_memory,
+        answers,
+    })
+}
+
+fn visualize<S: Solution>(
+    reader: &mut dyn BufRead,
+    part: Part,
+    params: &Params,
+    animation: &mut Animation<'_>,
+) -> Result<()> {
+    S::visualize(&parse::<S>(reader)?, params, part, animation)
+}
+
+fn parse<S: Solution>(reader: &mut dyn BufRead) -> Result<S::Input> {
+    let _span = trace::span(S::DAY, Level::Info, "parse");
+
+    S::Input::from_reader(reader).map_err(|err| {
+        let err = err.with_day(S::DAY);
+        trace::event(Level::Error, "failed", &[("error", &err.message())]);
+        err.into()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::params::Config;
+
+    const EXAMPLES: [(u8, &str); 16] = [
+        (1, include_str!("../examples/day01-1.txt")),
+        (2, include_str!("../examples/day02-1.txt")),
+        (3, include_str!("../examples/day03-1.txt")),
+        (4, include_str!("../examples/day04-1.txt")),
+        (5, include_str!("../examples/day05-1.txt")),
+        (6, include_str!("../examples/day06-1.txt")),
+        (7, include_str!("../examples/day07-1.txt")),
+        (8, include_str!("../examples/day08-1.txt")),
+        (9, include_str!("../examples/day09-1.txt")),
+        (9, include_str!("../examples/day09-2.txt")),
+        (10, include_str!("../examples/day10-1.txt")),
+        (11, include_str!("../examples/day11-1.txt")),
+        (12, include_str!("../examples/day12-1.txt")),
+        (13, include_str!("../examples/day13-1.txt")),
+        (14, include_str!("../examples/day14-1.txt")),
+        (15, include_str!("../examples/day15-1.txt")),
+    ];
+
+    /// Truncations and single character edits of `example`.
+    fn mutations(example: &str) -> impl Iterator<Item = String> + '_ {
+        const EDITS: [&str; 8] = ["", "\n", " ", "-", "[", ",", "0", "é"];
+
+        example.char_indices().flat_map(move |(idx, char)| {
+            let (head, tail) = (&example[..idx], &example[idx + char.len_utf8()..]);
+
+            std::iter::once(head.to_owned())
+                .chain(EDITS.iter().map(move |edit| format!("{head}{edit}{tail}")))
+        })
+    }
+
+    /// Each day must reject mutations of its examples, and the other days'
+    /// examples, or solve both parts of them, without panicking.
+    #[test]
+    fn parsers_reject_malformed_input_without_panicking() -> Result<()> {
+        // Ten thousand rounds of day 11 would take minutes over every mutation.
+        let mut config = Config::default();
+        config.push("day11.rounds_two=20")?;
+
+        DAYS.iter().for_each(|day| {
+            EXAMPLES
+                .iter()
+                .filter(|(example_day, _)| *example_day == day.day())
+                .flat_map(|(_, example)| mutations(example))
+                .chain(EXAMPLES.iter().map(|(_, example)| example.to_string()))
+                .chain([format!(
+                    "{}{}\n[]\n",
+                    "[".repeat(100_000),
+                    "]".repeat(100_000)
+                )])
+                .for_each(|input| {
+                    let _ = day.solve(input.as_bytes(), &day.parts(), &config.params(day));
+                });
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,230 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+
+/// Skeleton of a new day, with `{day}` standing in for the day number and
+/// `{day:02}` for it zero-padded.
+const TEMPLATE: &str = r#"use std::io::BufRead;
+use std::str::FromStr;
+
+use anyhow::Result;
+
+use crate::error::{ParseError, ParseResult};
+use crate::params::Params;
+use crate::records::parse_lines;
+use crate::{PuzzleInput, Solution, Unimplemented};
+
+pub struct Day{day};
+
+#[derive(Debug)]
+pub struct Line(pub String);
+
+impl FromStr for Line {
+    type Err = ParseError;
+
+    fn from_str(str: &str) -> ParseResult<Self> {
+        Ok(Self(str.to_owned()))
+    }
+}
+
+impl PuzzleInput for Vec<Line> {
+    fn from_reader(reader: impl BufRead) -> ParseResult<Self> {
+        parse_lines(reader, str::parse)
+    }
+}
+
+impl Solution for Day{day} {
+    const DAY: u8 = {day};
+
+    type Input = Vec<Line>;
+    type PartOne = usize;
+    type PartTwo = Unimplemented;
+
+    fn part_one(_lines: &Self::Input, _params: &Params) -> Result<usize> {
+        Err(Unimplemented.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../examples/day{day:02}-1.txt");
+
+    #[test]
+    fn parses_example() -> Result<()> {
+        <Day{day} as Solution>::Input::from_text(EXAMPLE)?;
+        Ok(())
+    }
+}
+"#;
+
+/// Adds day `day` to the crate at `root`: writes its module from the template,
+/// registers it in `src/lib.rs` and the runner's `DAYS`, and creates an empty
+/// example fixture and input placeholder. Returns every file written.
+pub fn scaffold(root: &Path, day: u8) -> Result<Vec<PathBuf>> {
+    if !(1..=25).contains(&day) {
+        return Err(anyhow!("Day must be between 1 and 25!"));
+    }
+
+    let module = root.join(format!("src/day{day}.rs"));
+
+    if module.exists() {
+        return Err(anyhow!("Day {day} already exists!"));
+    }
+
+    let lib = root.join("src/lib.rs");
+    let runner = root.join("src/runner.rs");
+
+    // Edit in memory first, so that nothing is written when either file has
+    // an unexpected layout.
+    let lib_source = register_module(&read(&lib)?, day)?;
+    let runner_source = register_day(&read(&runner)?, day)?;
+
+    let example = root.join(format!("examples/day{day:02}-1.txt"));
+    let input = root.join(format!("inputs/day{day:02}.txt"));
+
+    write(
+        &module,
+        &TEMPLATE
+            .replace("{day:02}", &format!("{day:02}"))
+            .replace("{day}", &day.to_string()),
+    )?;
+    write(&lib, &lib_source)?;
+    write(&runner, &runner_source)?;
+
+    [&example, &input]
+        .into_iter()
+        .filter(|path| !path.exists())
+        .try_for_each(|path| write(path, ""))?;
+
+    Ok(vec![module, lib, runner, example, input])
+}
+
+/// Adds `pub mod dayN;` among the other module declarations, in the order
+/// rustfmt keeps them.
+fn register_module(lib: &str, day: u8) -> Result<String> {
+    insert_sorted(lib, "pub mod ", &format!("pub mod day{day};"), |line| {
+        line.trim_start_matches("pub mod ")
+            .trim_end_matches(';')
+            .to_owned()
+    })
+    .ok_or_else(|| anyhow!("No module declarations in src/lib.rs!"))
+}
+
+/// Imports `DayN` in the runner and adds it to `DAYS`, in day order.
+fn register_day(runner: &str, day: u8) -> Result<String> {
+    let runner = insert_sorted(
+        runner,
+        "use crate::day",
+        &format!("use crate::day{day}::Day{day};"),
+        |line| line.split("::").nth(1).unwrap_or_default().to_owned(),
+    )
+    .ok_or_else(|| anyhow!("No day imports in src/runner.rs!"))?;
+
+    let mut lines = runner.lines().map(str::to_owned).collect::<Vec<_>>();
+
+    let start = lines
+        .iter()
+        .position(|line| line.starts_with("pub const DAYS: [Day; "))
+        .ok_or_else(|| anyhow!("No DAYS in src/runner.rs!"))?;
+    let end = lines[start..]
+        .iter()
+        .position(|line| line == "];")
+        .map(|len| start + len)
+        .ok_or_else(|| anyhow!("Unterminated DAYS in src/runner.rs!"))?;
+
+    let day_of = |line: &str| {
+        line.trim()
+            .trim_start_matches("Day::new::<Day")
+            .trim_end_matches(">(),")
+            .parse::<u8>()
+            .ok()
+    };
+
+    let at = (start + 1..end)
+        .find(|&idx| day_of(&lines[idx]).is_some_and(|other| other > day))
+        .unwrap_or(end);
+
+    lines.insert(at, format!("    Day::new::<Day{day}>(),"));
+    lines[start] = format!("pub const DAYS: [Day; {}] = [", end - start);
+
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Inserts `new` into the first run of lines starting with `prefix`, keeping
+/// that run sorted by `key`. Returns `None` when there is no such run.
+fn insert_sorted(
+    source: &str,
+    prefix: &str,
+    new: &str,
+    key: impl Fn(&str) -> String,
+) -> Option<String> {
+    let mut lines = source.lines().collect::<Vec<_>>();
+
+    let start = lines.iter().position(|line| line.starts_with(prefix))?;
+    let end = lines[start..]
+        .iter()
+        .position(|line| !line.starts_with(prefix))
+        .map_or(lines.len(), |len| start + len);
+
+    let at = (start..end)
+        .find(|&idx| key(lines[idx]) > key(new))
+        .unwrap_or(end);
+
+    lines.insert(at, new);
+
+    Some(lines.join("\n") + "\n")
+}
+
+fn read(path: &Path) -> Result<String> {
+    fs::read_to_string(path).map_err(|err| anyhow!("{}: {err}", path.display()))
+}
+
+fn write(path: &Path, contents: &str) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|err| anyhow!("{}: {err}", dir.display()))?;
+    }
+
+    fs::write(path, contents).map_err(|err| anyhow!("{}: {err}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIB: &str = "pub mod day1;\npub mod day10;\npub mod day2;\npub mod error;\n";
+
+    const RUNNER: &str = "\
+use crate::day1::Day1;
+use crate::day10::Day10;
+use crate::day2::Day2;
+use crate::params::Params;
+
+pub const DAYS: [Day; 3] = [
+    Day::new::<Day1>(),
+    Day::new::<Day2>(),
+    Day::new::<Day10>(),
+];
+";
+
+    #[test]
+    fn registers_new_day() -> Result<()> {
+        let lib = register_module(LIB, 3)?;
+        assert_eq!(
+            lib,
+            "pub mod day1;\npub mod day10;\npub mod day2;\npub mod day3;\npub mod error;\n"
+        );
+
+        let runner = register_day(RUNNER, 3)?;
+        assert!(
+            runner.contains("use crate::day2::Day2;\nuse crate::day3::Day3;\nuse crate::params")
+        );
+        assert!(runner.contains("pub const DAYS: [Day; 4] = ["));
+        assert!(runner.contains(
+            "    Day::new::<Day2>(),\n    Day::new::<Day3>(),\n    Day::new::<Day10>(),\n];"
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,270 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use anyhow::{anyhow, Result};
+
+use crate::http::Client;
+use crate::inputs::Inputs;
+use crate::runner::Part;
+use crate::submit::Outcome;
+
+pub const YEAR: u16 = 2022;
+pub const BASE_URL: &str = "https://adventofcode.com";
+
+/// Environment variable overriding [`BASE_URL`], e.g. to point at a local
+/// stand-in server.
+pub const BASE_URL_VAR: &str = "AOC_BASE_URL";
+
+/// Environment variable holding the session token, which takes precedence over
+/// the session file.
+pub const SESSION_VAR: &str = "AOC_SESSION";
+
+/// The site asks that private leaderboards be requested at most once every 15
+/// minutes.
+const LEADERBOARD_TTL: Duration = Duration::from_secs(15 * 60);
+
+/// The Advent of Code website, as seen by the holder of a session token.
+#[derive(Clone, Debug)]
+pub struct Site {
+    client: Client,
+    session: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Fetched {
+    Cached(PathBuf),
+    Downloaded(PathBuf),
+}
+
+impl Site {
+    pub fn new(base_url: &str, session: Option<String>) -> Self {
+        let client = match &session {
+            Some(session) => Client::new(base_url).with_cookie(format!("session={session}")),
+            None => Client::new(base_url),
+        };
+
+        Self { client, session }
+    }
+
+    /// The site at `base_url`, or else the one named by [`BASE_URL_VAR`] or
+    /// [`BASE_URL`], with the session token from [`SESSION_VAR`] or else the
+    /// first line of `session_file`. A missing session file is not an error
+    /// until a request needs the token.
+    pub fn from_env(base_url: Option<&str>, session_file: Option<&Path>) -> Result<Self> {
+        let base_url = match base_url {
+            Some(base_url) => base_url.to_owned(),
+            None => env::var(BASE_URL_VAR).unwrap_or_else(|_| BASE_URL.to_owned()),
+        };
+
+        let session = match env::var(SESSION_VAR) {
+            Ok(session) => Some(session),
+            Err(_) => match session_file
+                .map(PathBuf::from)
+                .or_else(default_session_file)
+            {
+                Some(path) if path.exists() => Some(
+                    fs::read_to_string(&path)
+                        .map_err(|err| anyhow!("{}: {err}", path.display()))?
+                        .lines()
+                        .next()
+                        .unwrap_or_default()
+                        .to_owned(),
+                ),
+                _ => None,
+            },
+        };
+
+        Ok(Self::new(
+            &base_url,
+            session.filter(|session| !session.trim().is_empty()),
+        ))
+    }
+
+    /// Saves the input of `day` to its path in `inputs`, unless it is already
+    /// there. Cached inputs are never downloaded again, but empty ones, such as
+    /// the placeholder `aoc new` leaves, do not count.
+    pub fn fetch_input(&self, inputs: &Inputs, day: u8) -> Result<Fetched> {
+        let path = inputs.input(day);
+
+        if fs::metadata(&path).is_ok_and(|metadata| metadata.len() > 0) {
+            return Ok(Fetched::Cached(path));
+        }
+
+        let response = self
+            .authenticated()?
+            .get(&format!("/{YEAR}/day/{day}/input"))?;
+
+        match response.status {
+            200 => {
+                save(&path, &response.body)?;
+                Ok(Fetched::Downloaded(path))
+            }
+            404 => Err(anyhow!("Not available yet!")),
+            400 | 500 => Err(anyhow!("Session token was rejected!")),
+            status => Err(anyhow!("Failed with status {status}!")),
+        }
+    }
+
+    /// Saves the JSON export of private leaderboard `id` to `path`, unless the
+    /// copy already there is recent enough.
+    pub fn fetch_leaderboard(&self, id: u64, path: &Path) -> Result<Fetched> {
+        let age = fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok());
+
+        if age.is_some_and(|age| age < LEADERBOARD_TTL) {
+            return Ok(Fetched::Cached(path.to_owned()));
+        }
+
+        let response = self
+            .authenticated()?
+            .get(&format!("/{YEAR}/leaderboard/private/view/{id}.json"))?;
+
+        match response.status {
+            // Without access to the leaderboard, the site redirects to the
+            // list of leaderboards instead.
+            200 if response.body.trim_start().starts_with('{') => {
+                save(path, &response.body)?;
+                Ok(Fetched::Downloaded(path.to_owned()))
+            }
+            200 | 302 => Err(anyhow!("No access to leaderboard {id}!")),
+            404 => Err(anyhow!("No such leaderboard!")),
+            400 | 500 => Err(anyhow!("Session token was rejected!")),
+            status => Err(anyhow!("Failed with status {status}!")),
+        }
+    }
+
+    /// Submits `answer` to `part` of `day`, returning the outcome and how many
+    /// seconds to wait before the next submission.
+    pub fn submit(&self, day: u8, part: Part, answer: &str) -> Result<(Outcome, u64)> {
+        let level = part.number().to_string();
+        let response = self.authenticated()?.post(
+            &format!("/{YEAR}/day/{day}/answer"),
+            &[("level", &level), ("answer", answer)],
+        )?;
+
+        match response.status {
+            200 => Outcome::from_page(&response.body),
+            400 | 500 => Err(anyhow!("Session token was rejected!")),
+            status => Err(anyhow!("Failed with status {status}!")),
+        }
+    }
+
+    fn authenticated(&self) -> Result<&Client> {
+        match self.session {
+            Some(_) => Ok(&self.client),
+            None => Err(anyhow!(
+                "Missing session token! Set {SESSION_VAR} or save it in {}.",
+                default_session_file()
+                    .unwrap_or_else(|| PathBuf::from("a session file"))
+                    .display()
+            )),
+        }
+    }
+}
+
+/// Writes next to `path` first, so that an interrupted write never leaves a
+/// truncated file in the cache.
+fn save(path: &Path, contents: &str) -> Result<()> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir).map_err(|err| anyhow!("{}: {err}", dir.display()))?;
+    }
+
+    let mut partial = path.as_os_str().to_owned();
+    partial.push(".part");
+
+    fs::write(&partial, contents)
+        .and_then(|_| fs::rename(&partial, path))
+        .map_err(|err| anyhow!("{}: {err}", path.display()))
+}
+
+/// `~/.config/aoc/session`.
+fn default_session_file() -> Option<PathBuf> {
+    env::var_os("HOME").map(|home| Path::new(&home).join(".config/aoc/session"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::mock;
+
+    #[test]
+    fn fetches_inputs_once() -> Result<()> {
+        let dir = env::temp_dir().join(format!("aoc-fetch-{}", std::process::id()));
+        let inputs = Inputs::new(&dir);
+
+        let (url, server) = mock::serve(vec![(200, "1000\n2000\n"), (404, ""), (200, "A Y\n")]);
+        let site = Site::new(&url, Some("abc".to_owned()));
+
+        assert_eq!(
+            site.fetch_input(&inputs, 1)?,
+            Fetched::Downloaded(inputs.input(1))
+        );
+        assert_eq!(
+            site.fetch_input(&inputs, 1)?,
+            Fetched::Cached(inputs.input(1))
+        );
+        assert_eq!(fs::read_to_string(inputs.input(1))?, "1000\n2000\n");
+
+        let err = site
+            .fetch_input(&inputs, 2)
+            .err()
+            .map(|err| err.to_string());
+        assert_eq!(err.as_deref(), Some("Not available yet!"));
+        assert!(!inputs.input(2).exists());
+
+        fs::write(inputs.input(3), "")?;
+        assert_eq!(
+            site.fetch_input(&inputs, 3)?,
+            Fetched::Downloaded(inputs.input(3))
+        );
+
+        let requests = server.join().unwrap();
+        assert_eq!(requests.len(), 3);
+        assert!(requests[0].starts_with("GET /2022/day/1/input HTTP/1.1\r\n"));
+        assert!(requests[0].contains("Cookie: session=abc\r\n"));
+
+        fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn fetches_leaderboards_at_most_every_15_minutes() -> Result<()> {
+        let path = env::temp_dir().join(format!("aoc-leaderboard-{}.json", std::process::id()));
+
+        let (url, server) = mock::serve(vec![(200, "{\"event\":\"2022\",\"members\":{}}")]);
+        let site = Site::new(&url, Some("abc".to_owned()));
+
+        assert_eq!(
+            site.fetch_leaderboard(42, &path)?,
+            Fetched::Downloaded(path.clone())
+        );
+        assert_eq!(
+            site.fetch_leaderboard(42, &path)?,
+            Fetched::Cached(path.clone())
+        );
+
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("GET /2022/leaderboard/private/view/42.json HTTP/1.1\r\n"));
+
+        fs::remove_file(path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn submits_answers() -> Result<()> {
+        let page = "<article><p>That's the right answer!</p></article>";
+        let (url, server) = mock::serve(vec![(200, page)]);
+        let site = Site::new(&url, Some("abc".to_owned()));
+
+        assert_eq!(site.submit(5, Part::Two, "MCD")?, (Outcome::Correct, 0));
+
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("POST /2022/day/5/answer HTTP/1.1\r\n"));
+        assert!(requests[0].ends_with("\r\n\r\nlevel=2&answer=MCD"));
+        Ok(())
+    }
+}
@@ -0,0 +1,386 @@
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use anyhow::{anyhow, Result};
+
+use crate::error::{number, ParseError, ParseResult};
+use crate::runner::Part;
+use crate::PuzzleInput;
+
+/// How long to hold off after a wrong answer when the site does not say.
+const DEFAULT_COOLDOWN: u64 = 60;
+
+/// The site's verdict on a submitted answer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Correct,
+    Incorrect,
+    TooHigh,
+    TooLow,
+    /// Submitted during a cooldown, so not checked at all.
+    Wait,
+    /// The part is locked or already solved.
+    WrongLevel,
+}
+
+/// A submitted answer and its outcome, along with how many seconds the site
+/// asked to wait before the next one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Attempt {
+    pub time: u64,
+    pub day: u8,
+    pub part: Part,
+    pub outcome: Outcome,
+    pub cooldown: u64,
+    pub answer: String,
+}
+
+/// Every answer ever submitted, one [`Attempt`] per line as `<unix time> <day>
+/// <part> <outcome> <cooldown> <answer>`. Each new attempt is appended.
+#[derive(Clone, Debug, Default)]
+pub struct History {
+    attempts: Vec<Attempt>,
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Correct => write!(fmt, "correct"),
+            Self::Incorrect => write!(fmt, "incorrect"),
+            Self::TooHigh => write!(fmt, "too-high"),
+            Self::TooLow => write!(fmt, "too-low"),
+            Self::Wait => write!(fmt, "wait"),
+            Self::WrongLevel => write!(fmt, "wrong-level"),
+        }
+    }
+}
+
+impl FromStr for Outcome {
+    type Err = ParseError;
+
+    fn from_str(str: &str) -> ParseResult<Self> {
+        match str {
+            "correct" => Ok(Self::Correct),
+            "incorrect" => Ok(Self::Incorrect),
+            "too-high" => Ok(Self::TooHigh),
+            "too-low" => Ok(Self::TooLow),
+            "wait" => Ok(Self::Wait),
+            "wrong-level" => Ok(Self::WrongLevel),
+            _ => Err(ParseError::invalid("Invalid outcome!", str)),
+        }
+    }
+}
+
+impl fmt::Display for Attempt {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            fmt,
+            "{} {} {} {} {} {}",
+            self.time,
+            self.day,
+            self.part.number(),
+            self.outcome,
+            self.cooldown,
+            self.answer
+        )
+    }
+}
+
+impl FromStr for Attempt {
+    type Err = ParseError;
+
+    fn from_str(str: &str) -> ParseResult<Self> {
+        let mut split = str.splitn(6, ' ');
+        let mut field = |name| {
+            split
+                .next()
+                .filter(|field| !field.is_empty())
+                .ok_or_else(|| ParseError::missing(name))
+        };
+
+        let time = number(field("Missing time!")?)?;
+        let day = number(field("Missing day!")?)?;
+        let part = field("Missing part!")?;
+        let part = part
+            .parse()
+            .map_err(|_| ParseError::invalid("Invalid part!", part))?;
+        let outcome = field("Missing outcome!")?.parse()?;
+        let cooldown = number(field("Missing cooldown!")?)?;
+        let answer = field("Missing answer!")?.to_owned();
+
+        Ok(Self {
+            time,
+            day,
+            part,
+            outcome,
+            cooldown,
+            answer,
+        })
+    }
+}
+
+impl PuzzleInput for History {
+    fn from_reader(reader: impl BufRead) -> ParseResult<Self> {
+        let attempts = reader
+            .lines()
+            .zip(1..)
+            .filter(|(line, _)| line.as_ref().map_or(true, |line| !line.is_empty()))
+            .map(|(line, idx)| {
+                let line = line?;
+                line.parse()
+                    .map_err(|err: ParseError| err.with_line(idx, &line))
+            })
+            .collect::<ParseResult<_>>()?;
+
+        Ok(Self { attempts })
+    }
+}
+
+impl Outcome {
+    /// Reads the outcome, and the cooldown in seconds, from the page the site
+    /// answers a submission with.
+    pub fn from_page(page: &str) -> Result<(Self, u64)> {
+        let text = article_text(page);
+
+        let outcome = match text.as_str() {
+            text if text.contains("That's the right answer") => Self::Correct,
+            text if text.contains("your answer is too high") => Self::TooHigh,
+            text if text.contains("your answer is too low") => Self::TooLow,
+            text if text.contains("That's not the right answer") => Self::Incorrect,
+            text if text.contains("You gave an answer too recently") => Self::Wait,
+            text if text.contains("You don't seem to be solving the right level") => {
+                Self::WrongLevel
+            }
+            _ => return Err(anyhow!("Unrecognized response: {}", text.trim())),
+        };
+
+        let cooldown = match outcome {
+            Self::Correct | Self::WrongLevel => 0,
+            Self::Wait => left_to_wait(&text).unwrap_or(DEFAULT_COOLDOWN),
+            _ => wait_before_retrying(&text).unwrap_or(DEFAULT_COOLDOWN),
+        };
+
+        Ok((outcome, cooldown))
+    }
+
+    fn is_wrong(&self) -> bool {
+        matches!(self, Self::Incorrect | Self::TooHigh | Self::TooLow)
+    }
+}
+
+impl History {
+    /// Loads the history at `path`, which starts out empty when there is no
+    /// such file yet.
+    pub fn load(path: &Path) -> Result<Self> {
+        match path.exists() {
+            true => Self::from_path(path).map_err(|err| anyhow!("{}: {err}", path.display())),
+            false => Ok(Self::default()),
+        }
+    }
+
+    pub fn attempts(&self) -> &[Attempt] {
+        &self.attempts
+    }
+
+    /// Checks `answer` against earlier attempts at time `now`: a part solved
+    /// before, an answer already known to be wrong, or a cooldown still
+    /// running are all refused without asking the site.
+    pub fn check(&self, day: u8, part: Part, answer: &str, now: u64) -> Result<()> {
+        let earlier = self
+            .attempts
+            .iter()
+            .filter(|attempt| attempt.day == day && attempt.part == part);
+
+        if let Some(solved) = earlier
+            .clone()
+            .find(|attempt| attempt.outcome == Outcome::Correct)
+        {
+            return Err(anyhow!("Already solved with {}!", solved.answer));
+        }
+
+        if let Some(wrong) = earlier
+            .clone()
+            .find(|attempt| attempt.answer == answer && attempt.outcome.is_wrong())
+        {
+            return Err(anyhow!(
+                "{answer} was already submitted and was {}!",
+                wrong.outcome
+            ));
+        }
+
+        if let Ok(value) = answer.parse::<i128>() {
+            let bound = earlier.clone().find(|attempt| {
+                match (attempt.outcome, attempt.answer.parse::<i128>()) {
+                    (Outcome::TooHigh, Ok(high)) => value >= high,
+                    (Outcome::TooLow, Ok(low)) => value <= low,
+                    _ => false,
+                }
+            });
+
+            if let Some(bound) = bound {
+                return Err(anyhow!(
+                    "{answer} must be {} too, as {} was!",
+                    bound.outcome,
+                    bound.answer
+                ));
+            }
+        }
+
+        match self
+            .attempts
+            .iter()
+            .map(|attempt| attempt.time + attempt.cooldown)
+            .max()
+        {
+            Some(until) if until > now => {
+                Err(anyhow!("Wait {}s before submitting again!", until - now))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Adds `attempt`, appending it to the file at `path` as well.
+    pub fn record(&mut self, path: &Path, attempt: Attempt) -> Result<()> {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir).map_err(|err| anyhow!("{}: {err}", dir.display()))?;
+        }
+
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .and_then(|mut file| writeln!(file, "{attempt}"))
+            .map_err(|err| anyhow!("{}: {err}", path.display()))?;
+
+        self.attempts.push(attempt);
+        Ok(())
+    }
+}
+
+/// Default history file, next to the inputs.
+pub fn history_path(inputs: &Path) -> PathBuf {
+    inputs.join("submissions.txt")
+}
+
+/// Text of the page's `<article>`, or the whole page without one, with tags
+/// stripped.
+fn article_text(page: &str) -> String {
+    let article = page
+        .split_once("<article")
+        .and_then(|(_, rest)| rest.split_once("</article>"))
+        .map_or(page, |(article, _)| article);
+
+    let mut in_tag = false;
+
+    article
+        .chars()
+        .filter(|&char| match char {
+            '<' => {
+                in_tag = true;
+                false
+            }
+            '>' => {
+                in_tag = false;
+                false
+            }
+            _ => !in_tag,
+        })
+        .collect()
+}
+
+/// Reads `You have 1m 30s left to wait`.
+fn left_to_wait(text: &str) -> Option<u64> {
+    let (before, _) = text.split_once(" left to wait")?;
+    let (_, time) = before.rsplit_once("You have ")?;
+
+    time.split_whitespace()
+        .map(|part| match part.split_at(part.len() - 1) {
+            (secs, "s") => secs.parse::<u64>().ok(),
+            (mins, "m") => mins.parse::<u64>().ok().map(|mins| mins * 60),
+            _ => None,
+        })
+        .sum()
+}
+
+/// Reads `please wait one minute before trying again` or `wait 5 minutes`.
+fn wait_before_retrying(text: &str) -> Option<u64> {
+    let (before, _) = text.split_once(" before trying again")?;
+    let (_, wait) = before.rsplit_once("wait ")?;
+    let (count, unit) = wait.split_once(' ')?;
+
+    let count = match count {
+        "one" => 1,
+        count => count.parse().ok()?,
+    };
+
+    match unit.trim_end_matches('s') {
+        "minute" => Some(count * 60),
+        "second" => Some(count),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(text: &str) -> String {
+        format!("<html><main><article><p>{text}</p></article></main></html>")
+    }
+
+    #[test]
+    fn parses_outcomes() -> Result<()> {
+        let cases = [
+            ("That's the right answer! You are <em>one gold star</em> closer.", (Outcome::Correct, 0)),
+            ("That's not the right answer; your answer is too high. Please wait one minute before trying again.", (Outcome::TooHigh, 60)),
+            ("That's not the right answer; your answer is too low. Please wait 5 minutes before trying again.", (Outcome::TooLow, 300)),
+            ("That's not the right answer. Please wait one minute before trying again.", (Outcome::Incorrect, 60)),
+            ("You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 1m 5s left to wait.", (Outcome::Wait, 65)),
+            ("You don't seem to be solving the right level.  Did you already complete it?", (Outcome::WrongLevel, 0)),
+        ];
+
+        cases.into_iter().try_for_each(|(text, expected)| {
+            assert_eq!(Outcome::from_page(&page(text))?, expected, "{text}");
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn refuses_known_answers_and_cooldowns() -> Result<()> {
+        let history = History::from_text(
+            "50 2 1 correct 0 CMZ\n\
+             100 1 1 too-high 60 500\n\
+             200 1 1 too-low 60 100\n",
+        )?;
+
+        let refused = |answer: &str, now| {
+            history
+                .check(1, Part::One, answer, now)
+                .err()
+                .map(|err| err.to_string())
+        };
+
+        assert_eq!(
+            refused("500", 1000).as_deref(),
+            Some("500 was already submitted and was too-high!")
+        );
+        assert_eq!(
+            refused("600", 1000).as_deref(),
+            Some("600 must be too-high too, as 500 was!")
+        );
+        assert_eq!(
+            refused("50", 1000).as_deref(),
+            Some("50 must be too-low too, as 100 was!")
+        );
+        assert_eq!(
+            refused("250", 230).as_deref(),
+            Some("Wait 30s before submitting again!")
+        );
+        assert_eq!(refused("250", 1000), None);
+        assert!(history.check(2, Part::One, "MCD", 1000).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,297 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::env;
+use std::fmt;
+use std::io::{self, Write};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Error, Result};
+
+/// Environment variable choosing what to trace, as read by [`init`]. Nothing
+/// is traced without it.
+pub const TRACE_VAR: &str = "AOC_TRACE";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Level {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+/// The most verbose level to trace, per day, from comma-separated directives:
+/// `<level>` applies to every day and `day<n>=<level>` to day `n` alone, with
+/// `off` tracing nothing. `info,day11=trace` traces the spans of every day,
+/// along with each throw of day 11.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Filter {
+    default: Option<Level>,
+    days: BTreeMap<u8, Option<Level>>,
+}
+
+/// A traced stretch of work on a day, such as parsing its input, which lasts
+/// until dropped. Events in between are attributed to its day.
+#[must_use]
+pub struct Span {
+    level: Level,
+    /// When the span was entered, if its level is traced at all.
+    entered: Option<Instant>,
+    pushed: bool,
+}
+
+/// A single line of trace output: `[<time>] <LEVEL> day<n>/<span>: <message>
+/// <key>=<value>...`, with the time since tracing started.
+struct Record<'a> {
+    time: Duration,
+    level: Level,
+    spans: &'a [(u8, &'static str)],
+    message: &'a str,
+    fields: &'a [(&'a str, &'a dyn fmt::Display)],
+}
+
+static FILTER: OnceLock<Filter> = OnceLock::new();
+static START: OnceLock<Instant> = OnceLock::new();
+
+/// One past the most verbose level traced on any day, or zero when nothing is,
+/// so that an event that is not traced costs a single load.
+static MAX_LEVEL: AtomicU8 = AtomicU8::new(0);
+
+thread_local! {
+    /// Spans open on this thread, innermost last.
+    static SPANS: RefCell<Vec<(u8, &'static str)>> = const { RefCell::new(Vec::new()) };
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.pad(match self {
+            Self::Error => "ERROR",
+            Self::Warn => "WARN",
+            Self::Info => "INFO",
+            Self::Debug => "DEBUG",
+            Self::Trace => "TRACE",
+        })
+    }
+}
+
+impl FromStr for Level {
+    type Err = Error;
+
+    fn from_str(str: &str) -> Result<Self> {
+        match str.to_ascii_lowercase().as_str() {
+            "error" => Ok(Self::Error),
+            "warn" => Ok(Self::Warn),
+            "info" => Ok(Self::Info),
+            "debug" => Ok(Self::Debug),
+            "trace" => Ok(Self::Trace),
+            _ => Err(anyhow!("Invalid level '{str}'!")),
+        }
+    }
+}
+
+impl FromStr for Filter {
+    type Err = Error;
+
+    fn from_str(str: &str) -> Result<Self> {
+        let level = |level: &str| match level {
+            "off" => Ok(None),
+            level => level.parse().map(Some),
+        };
+
+        str.split(',')
+            .map(str::trim)
+            .filter(|directive| !directive.is_empty())
+            .try_fold(Self::default(), |mut filter, directive| {
+                match directive.split_once('=') {
+                    Some((day, max)) => {
+                        let day = day
+                            .strip_prefix("day")
+                            .and_then(|day| day.parse().ok())
+                            .ok_or_else(|| anyhow!("Invalid day '{day}'!"))?;
+
+                        filter.days.insert(day, level(max)?);
+                    }
+                    None => filter.default = level(directive)?,
+                }
+
+                Ok(filter)
+            })
+    }
+}
+
+impl Filter {
+    /// Whether to trace `level` on `day`, where events outside of any span
+    /// have no day.
+    pub fn enabled(&self, day: Option<u8>, level: Level) -> bool {
+        day.and_then(|day| self.days.get(&day))
+            .copied()
+            .unwrap_or(self.default)
+            .is_some_and(|max| level <= max)
+    }
+
+    fn max_level(&self) -> Option<Level> {
+        self.days
+            .values()
+            .chain([&self.default])
+            .flatten()
+            .max()
+            .copied()
+    }
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        if let Some(entered) = self.entered {
+            let elapsed = format!("{:.2?}", entered.elapsed());
+            write(self.level, "exit", &[("elapsed", &elapsed)]);
+        }
+
+        if self.pushed {
+            SPANS.with_borrow_mut(Vec::pop);
+        }
+    }
+}
+
+impl fmt::Display for Record<'_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "[{:>10.3?}] {:<5} ", self.time, self.level)?;
+
+        if let Some((day, _)) = self.spans.last() {
+            write!(fmt, "day{day}")?;
+            self.spans
+                .iter()
+                .try_for_each(|(_, name)| write!(fmt, "/{name}"))?;
+            write!(fmt, ": ")?;
+        }
+
+        write!(fmt, "{}", self.message)?;
+
+        self.fields
+            .iter()
+            .try_for_each(|(key, value)| write!(fmt, " {key}={value}"))
+    }
+}
+
+/// Starts tracing to stderr as [`TRACE_VAR`] says. Only the first call has any
+/// effect.
+pub fn init() -> Result<()> {
+    let filter = match env::var(TRACE_VAR) {
+        Ok(filter) => filter
+            .parse::<Filter>()
+            .map_err(|err| anyhow!("{TRACE_VAR}: {err}"))?,
+        Err(_) => return Ok(()),
+    };
+
+    if let Some(max) = filter.max_level() {
+        START.get_or_init(Instant::now);
+
+        if FILTER.set(filter).is_ok() {
+            MAX_LEVEL.store(max as u8 + 1, Ordering::Relaxed);
+        }
+    }
+
+    Ok(())
+}
+
+/// Enters a span named `name` on `day`, tracing its entry and, once dropped,
+/// its exit along with the time spent in it.
+pub fn span(day: u8, level: Level, name: &'static str) -> Span {
+    if MAX_LEVEL.load(Ordering::Relaxed) == 0 {
+        return Span {
+            level,
+            entered: None,
+            pushed: false,
+        };
+    }
+
+    SPANS.with_borrow_mut(|spans| spans.push((day, name)));
+
+    let entered = enabled(Some(day), level).then(|| {
+        write(level, "enter", &[]);
+        Instant::now()
+    });
+
+    Span {
+        level,
+        entered,
+        pushed: true,
+    }
+}
+
+/// Traces `message` along with `fields`, in the innermost span on this thread.
+/// The fields are only formatted when the event is traced.
+pub fn event(level: Level, message: &str, fields: &[(&str, &dyn fmt::Display)]) {
+    if level as u8 >= MAX_LEVEL.load(Ordering::Relaxed) {
+        return;
+    }
+
+    let day = SPANS.with_borrow(|spans| spans.last().map(|&(day, _)| day));
+
+    if enabled(day, level) {
+        write(level, message, fields);
+    }
+}
+
+fn enabled(day: Option<u8>, level: Level) -> bool {
+    FILTER
+        .get()
+        .is_some_and(|filter| filter.enabled(day, level))
+}
+
+fn write(level: Level, message: &str, fields: &[(&str, &dyn fmt::Display)]) {
+    let time = START.get().map(Instant::elapsed).unwrap_or_default();
+
+    SPANS.with_borrow(|spans| {
+        let record = Record {
+            time,
+            level,
+            spans,
+            message,
+            fields,
+        };
+
+        // Tracing must never fail a solution, even with stderr gone.
+        let _ = writeln!(io::stderr().lock(), "{record}");
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filters_by_day_and_level() -> Result<()> {
+        let filter = "info, day11=trace, day14=off".parse::<Filter>()?;
+
+        assert!(filter.enabled(Some(1), Level::Info));
+        assert!(!filter.enabled(Some(1), Level::Debug));
+        assert!(filter.enabled(Some(11), Level::Trace));
+        assert!(!filter.enabled(Some(14), Level::Error));
+        assert!(filter.enabled(None, Level::Warn));
+        assert_eq!(filter.max_level(), Some(Level::Trace));
+
+        assert_eq!(Filter::from_str("")?.max_level(), None);
+        assert!("day=info".parse::<Filter>().is_err());
+        assert!("verbose".parse::<Filter>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn formats_records() {
+        let record = Record {
+            time: Duration::from_micros(1500),
+            level: Level::Trace,
+            spans: &[(11, "part one")],
+            message: "throw",
+            fields: &[("from", &0), ("to", &3), ("worry", &500)],
+        };
+
+        assert_eq!(
+            record.to_string(),
+            "[   1.500ms] TRACE day11/part one: throw from=0 to=3 worry=500"
+        );
+    }
+}
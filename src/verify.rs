@@ -0,0 +1,253 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::BufRead;
+
+use anyhow::{Error, Result};
+
+use crate::error::{number, ParseError, ParseResult};
+use crate::params::Params;
+use crate::runner::{Day, Part};
+use crate::{PuzzleInput, Unimplemented};
+
+/// Where `aoc verify` looks for expected answers to the inputs by default.
+/// Unlike inputs, answers are meant to be checked in.
+pub const ANSWERS_FILE: &str = "answers.txt";
+
+/// Expected answers to each day's first example, which `aoc verify` checks
+/// unless given inputs.
+pub const EXAMPLE_ANSWERS_FILE: &str = "examples/answers.txt";
+
+/// Parameters the examples need, such as day 15's row.
+pub const EXAMPLE_CONFIG_FILE: &str = "examples/config.txt";
+
+/// Expected answers, one per line as `<day> <part> <answer>`. Blank lines and
+/// lines starting with `#` are ignored.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Answers {
+    answers: BTreeMap<(u8, Part), String>,
+}
+
+#[derive(Debug)]
+pub enum Verdict {
+    Correct(String),
+    Incorrect { expected: String, actual: String },
+    Missing(String),
+    Unimplemented(Option<String>),
+    Failed(Error),
+}
+
+impl PuzzleInput for Answers {
+    fn from_reader(reader: impl BufRead) -> ParseResult<Self> {
+        let mut answers = BTreeMap::new();
+
+        for (line, idx) in reader.lines().zip(1..) {
+            let line = line?;
+            let text = line.trim();
+
+            if text.is_empty() || text.starts_with('#') {
+                continue;
+            }
+
+            let (key, answer) = parse_answer(text).map_err(|err| err.with_line(idx, &line))?;
+
+            if answers.insert(key, answer.to_owned()).is_some() {
+                return Err(ParseError::invalid("Duplicate answer!", text).with_line(idx, &line));
+            }
+        }
+
+        Ok(Self { answers })
+    }
+}
+
+fn parse_answer(line: &str) -> ParseResult<((u8, Part), &str)> {
+    let mut split = line.splitn(3, char::is_whitespace);
+
+    match (split.next(), split.next(), split.next()) {
+        (Some(day), Some(part), Some(answer)) => {
+            let part = part
+                .parse()
+                .map_err(|_| ParseError::invalid("Invalid part!", part))?;
+
+            Ok(((number(day)?, part), answer.trim()))
+        }
+        _ => Err(ParseError::missing("Expected '<day> <part> <answer>'!")),
+    }
+}
+
+impl Answers {
+    pub fn get(&self, day: u8, part: Part) -> Option<&str> {
+        self.answers.get(&(day, part)).map(|answer| answer.as_str())
+    }
+
+    /// Parts of `day` with an expected answer.
+    pub fn parts(&self, day: u8) -> Vec<Part> {
+        Part::ALL
+            .into_iter()
+            .filter(|&part| self.get(day, part).is_some())
+            .collect()
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Correct(answer) => write!(fmt, "ok ({answer})"),
+            Self::Incorrect { expected, actual } => {
+                write!(fmt, "mismatch (expected {expected}, got {actual})")
+            }
+            Self::Missing(actual) => write!(fmt, "no expected answer (got {actual})"),
+            Self::Unimplemented(None) => write!(fmt, "not implemented"),
+            Self::Unimplemented(Some(expected)) => {
+                write!(fmt, "not implemented (expected {expected})")
+            }
+            Self::Failed(err) => write!(fmt, "error ({err:#})"),
+        }
+    }
+}
+
+impl Verdict {
+    /// Whether the verdict passes verification. An unimplemented part only
+    /// fails when the manifest expects an answer for it.
+    pub fn is_ok(&self) -> bool {
+        matches!(self, Self::Correct(_) | Self::Unimplemented(None))
+    }
+}
+
+/// Solves `parts` of `day` and checks each answer against `answers`. A parse
+/// failure fails the whole day, as it does in [`Day::solve`].
+pub fn verify(
+    day: &Day,
+    reader: impl BufRead,
+    parts: &[Part],
+    params: &Params,
+    answers: &Answers,
+) -> Result<Vec<(Part, Verdict)>> {
+    let solvable = day
+        .parts()
+        .into_iter()
+        .filter(|part| parts.contains(part))
+        .collect::<Vec<_>>();
+    let mut solved = day.solve(reader, solvable.as_slice(), params)?;
+
+    Ok(parts
+        .iter()
+        .map(|&part| {
+            let expected = answers.get(day.day(), part).map(str::to_owned);
+
+            let verdict = match solved.iter().position(|answer| answer.part == part) {
+                Some(idx) => match (solved.remove(idx).value, expected) {
+                    (Ok(actual), Some(expected)) if actual.trim() == expected => {
+                        Verdict::Correct(expected)
+                    }
+                    (Ok(actual), Some(expected)) => Verdict::Incorrect { expected, actual },
+                    (Ok(actual), None) => Verdict::Missing(actual),
+                    (Err(err), expected) if err.is::<Unimplemented>() => {
+                        Verdict::Unimplemented(expected)
+                    }
+                    (Err(err), _) => Verdict::Failed(err),
+                },
+                None => Verdict::Unimplemented(expected),
+            };
+
+            (part, verdict)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inputs::Inputs;
+    use crate::params::Config;
+    use crate::runner::DAYS;
+    use anyhow::anyhow;
+
+    fn verdicts(day: u8, input: &str, answers: &str) -> Result<Vec<(Part, bool, String)>> {
+        let day = Day::find(day)?;
+        let params = day.params();
+        let params = match day.day() {
+            15 => params.with("y", "10")?,
+            _ => params,
+        };
+
+        Ok(verify(
+            &day,
+            input.as_bytes(),
+            &Part::ALL,
+            &params,
+            &Answers::from_text(answers)?,
+        )?
+        .into_iter()
+        .map(|(part, verdict)| (part, verdict.is_ok(), verdict.to_string()))
+        .collect())
+    }
+
+    #[test]
+    fn checks_answers() -> Result<()> {
+        let example = include_str!("../examples/day01-1.txt");
+
+        assert_eq!(
+            verdicts(1, example, "1 1 24000\n1 2 12345\n")?,
+            [
+                (Part::One, true, "ok (24000)".to_owned()),
+                (
+                    Part::Two,
+                    false,
+                    "mismatch (expected 12345, got 45000)".to_owned()
+                ),
+            ]
+        );
+        assert_eq!(
+            verdicts(1, example, "# Part one only\n1 1 24000\n")?[1],
+            (
+                Part::Two,
+                false,
+                "no expected answer (got 45000)".to_owned()
+            )
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn fails_unimplemented_parts_only_when_expected() -> Result<()> {
+        let example = include_str!("../examples/day15-1.txt");
+
+        assert_eq!(
+            verdicts(15, example, "15 1 26\n")?,
+            [
+                (Part::One, true, "ok (26)".to_owned()),
+                (Part::Two, true, "not implemented".to_owned()),
+            ]
+        );
+        assert_eq!(
+            verdicts(15, example, "15 1 26\n15 2 56000011\n")?[1],
+            (
+                Part::Two,
+                false,
+                "not implemented (expected 56000011)".to_owned()
+            )
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn examples_match_their_answers() -> Result<()> {
+        let answers = Answers::from_path(EXAMPLE_ANSWERS_FILE)?;
+        let config = Config::from_path(EXAMPLE_CONFIG_FILE)?;
+        let inputs = Inputs::default();
+
+        DAYS.iter()
+            .filter(|day| !answers.parts(day.day()).is_empty())
+            .try_for_each(|day| {
+                let reader = inputs.open(day.day(), Some(1))?;
+                let parts = answers.parts(day.day());
+
+                verify(day, reader, &parts, &config.params(day), &answers)?
+                    .into_iter()
+                    .try_for_each(|(part, verdict)| match verdict.is_ok() {
+                        true => Ok(()),
+                        false => Err(anyhow!("Day {} part {part}: {verdict}", day.day())),
+                    })
+            })
+    }
+}
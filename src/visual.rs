@@ -0,0 +1,150 @@
+use std::io::Write;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+
+use crate::geometry::{BoundingBox, Point};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mode {
+    /// Redraws each frame in place with ANSI escapes, at most `fps` a second.
+    Terminal { fps: u32 },
+    /// Writes every frame as plain text under a `Frame N` header, as fast as
+    /// they come.
+    Plain,
+}
+
+/// Sink for the frames of an animated simulation. Simulations report every
+/// step with [`Animation::frame`] and their final state with
+/// [`Animation::finish`], and only render the frames that get drawn.
+pub struct Animation<'a> {
+    out: Box<dyn Write + 'a>,
+    mode: Mode,
+    every: usize,
+    frames: usize,
+    drawn: Option<Instant>,
+}
+
+impl<'a> Animation<'a> {
+    pub fn new(out: impl Write + 'a, mode: Mode) -> Self {
+        Self {
+            out: Box::new(out),
+            mode,
+            every: 1,
+            frames: 0,
+            drawn: None,
+        }
+    }
+
+    /// Draws only every `every`-th frame, to speed up long simulations. The
+    /// final frame is always drawn.
+    pub fn every(mut self, every: usize) -> Self {
+        self.every = every.max(1);
+        self
+    }
+
+    /// Number of frames reported so far, drawn or not.
+    pub fn frames(&self) -> usize {
+        self.frames
+    }
+
+    pub fn frame(&mut self, render: impl FnOnce() -> String) -> Result<()> {
+        self.frames += 1;
+
+        match (self.frames - 1).is_multiple_of(self.every) {
+            true => self.draw(&render()),
+            false => Ok(()),
+        }
+    }
+
+    /// Draws the final state, and hands the terminal back.
+    pub fn finish(&mut self, render: impl FnOnce() -> String) -> Result<()> {
+        self.frames += 1;
+        self.draw(&render())?;
+
+        if let Mode::Terminal { .. } = self.mode {
+            write!(self.out, "\x1b[?25h")?;
+        }
+
+        self.out.flush()?;
+        Ok(())
+    }
+
+    fn draw(&mut self, frame: &str) -> Result<()> {
+        match self.mode {
+            Mode::Terminal { fps } => {
+                if let Some(drawn) = self.drawn {
+                    let frame_time = Duration::from_secs(1) / fps.max(1);
+                    thread::sleep(frame_time.saturating_sub(drawn.elapsed()));
+                } else {
+                    // Hide the cursor and start from a blank screen.
+                    write!(self.out, "\x1b[?25l\x1b[2J")?;
+                }
+
+                write!(self.out, "\x1b[H")?;
+                frame
+                    .lines()
+                    .try_for_each(|line| writeln!(self.out, "{line}\x1b[K"))?;
+                write!(self.out, "\x1b[J")?;
+            }
+            Mode::Plain => {
+                writeln!(self.out, "Frame {}", self.frames)?;
+                frame
+                    .lines()
+                    .try_for_each(|line| writeln!(self.out, "{line}"))?;
+                writeln!(self.out)?;
+            }
+        }
+
+        self.drawn = Some(Instant::now());
+        self.out.flush()?;
+        Ok(())
+    }
+}
+
+/// Renders `bounds` row by row, with `cell` drawing each point.
+pub fn render(bounds: &BoundingBox, mut cell: impl FnMut(Point) -> char) -> String {
+    (bounds.min.y..=bounds.max.y)
+        .map(|y| {
+            (bounds.min.x..=bounds.max.x)
+                .map(|x| cell(Point::new(x, y)))
+                .chain(['\n'])
+                .collect::<String>()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn draws_every_nth_and_final_frame() -> Result<()> {
+        let mut out = vec![];
+        let mut animation = Animation::new(&mut out, Mode::Plain).every(2);
+
+        (0..4).try_for_each(|frame| animation.frame(|| format!("{frame}")))?;
+        animation.finish(|| "done".to_owned())?;
+        drop(animation);
+
+        assert_eq!(
+            String::from_utf8(out)?,
+            "Frame 1\n0\n\nFrame 3\n2\n\nFrame 5\ndone\n\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn renders_bounds() {
+        let bounds = BoundingBox::from_points([Point::new(-1, 0), Point::new(1, 1)]);
+        let frame = bounds.map(|bounds| {
+            render(&bounds, |point| match point.x {
+                0 => '|',
+                _ => '.',
+            })
+        });
+
+        assert_eq!(frame.as_deref(), Some(".|.\n.|.\n"));
+    }
+}
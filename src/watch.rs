@@ -0,0 +1,215 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use anyhow::anyhow;
+
+use crate::error::summary;
+use crate::inputs::{self, Inputs};
+use crate::params::Params;
+use crate::pool;
+use crate::runner::{Day, Part};
+
+/// Keeps track of when files last changed, by polling their modification time
+/// and length. Missing files are watched too, and change when they appear.
+#[derive(Clone, Debug)]
+pub struct Poller {
+    files: Vec<(PathBuf, Option<(SystemTime, u64)>)>,
+}
+
+/// Answers of every watched file and part, with failures as a one-line
+/// message.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Snapshot(BTreeMap<(PathBuf, Part), Result<String, String>>);
+
+/// Answers compared to an earlier snapshot, one line each, marked `+` when new,
+/// `-` when gone and `~` when changed.
+pub struct Diff<'a>(pub &'a Snapshot, pub &'a Snapshot);
+
+impl Poller {
+    pub fn new(files: Vec<PathBuf>) -> Self {
+        Self {
+            files: files
+                .into_iter()
+                .map(|path| {
+                    let stamp = stamp(&path);
+                    (path, stamp)
+                })
+                .collect(),
+        }
+    }
+
+    /// Files that changed since the previous poll.
+    pub fn poll(&mut self) -> Vec<&Path> {
+        self.files
+            .iter_mut()
+            .filter_map(|(path, last)| {
+                let stamp = stamp(path);
+
+                match stamp == *last {
+                    true => None,
+                    false => {
+                        *last = stamp;
+                        Some(path.as_path())
+                    }
+                }
+            })
+            .collect()
+    }
+}
+
+impl Snapshot {
+    /// Solves `parts` of `day` from each of `files`. A panic fails every part
+    /// of its file rather than ending the watch.
+    pub fn take(day: &Day, files: &[PathBuf], parts: &[Part], params: &Params) -> Self {
+        Self(
+            files
+                .iter()
+                .flat_map(|path| {
+                    let answers = panic::catch_unwind(AssertUnwindSafe(|| {
+                        inputs::open(path).and_then(|reader| day.solve(reader, parts, params))
+                    }))
+                    .unwrap_or_else(|panic| Err(anyhow!("Panicked: {}", pool::message(panic))))
+                    .map_err(|err| summary(&err));
+
+                    let answers: Vec<_> = match answers {
+                        Ok(answers) => answers
+                            .into_iter()
+                            .map(|answer| (answer.part, answer.value.map_err(|err| summary(&err))))
+                            .collect(),
+                        Err(err) => parts.iter().map(|&part| (part, Err(err.clone()))).collect(),
+                    };
+
+                    answers
+                        .into_iter()
+                        .map(|(part, value)| ((path.clone(), part), value))
+                        .collect::<Vec<_>>()
+                })
+                .collect(),
+        )
+    }
+}
+
+impl fmt::Display for Snapshot {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0
+            .iter()
+            .try_for_each(|(key, value)| writeln!(fmt, "  {}", Line(key, value)))
+    }
+}
+
+impl fmt::Display for Diff<'_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (before, after) = (&self.0 .0, &self.1 .0);
+
+        after
+            .iter()
+            .try_for_each(|(key, value)| match before.get(key) {
+                None => writeln!(fmt, "+ {}", Line(key, value)),
+                Some(old) if old == value => writeln!(fmt, "  {}", Line(key, value)),
+                Some(old) => writeln!(fmt, "~ {} (was {})", Line(key, value), Value(old)),
+            })?;
+
+        before
+            .iter()
+            .filter(|(key, _)| !after.contains_key(key))
+            .try_for_each(|(key, value)| writeln!(fmt, "- {}", Line(key, value)))
+    }
+}
+
+/// A single answer, as `<file> part <part>: <answer>`.
+struct Line<'a>(&'a (PathBuf, Part), &'a Result<String, String>);
+
+struct Value<'a>(&'a Result<String, String>);
+
+impl fmt::Display for Line<'_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (path, part) = self.0;
+        write!(fmt, "{} part {part}: {}", path.display(), Value(self.1))
+    }
+}
+
+impl fmt::Display for Value<'_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Ok(answer) => write!(fmt, "{answer}"),
+            Err(err) => write!(fmt, "error: {err}"),
+        }
+    }
+}
+
+/// The input of `day` along with every example of it there is, numbered from
+/// one without gaps.
+pub fn files(inputs: &Inputs, day: u8) -> Vec<PathBuf> {
+    let examples = (1..)
+        .map(|example| inputs.example(day, example))
+        .take_while(|path| path.exists());
+
+    [inputs.input(day)].into_iter().chain(examples).collect()
+}
+
+fn stamp(path: &Path) -> Option<(SystemTime, u64)> {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diffs_snapshots() {
+        let snapshot = |answers: &[(&str, Part, Result<&str, &str>)]| {
+            Snapshot(
+                answers
+                    .iter()
+                    .map(|(path, part, value)| {
+                        let value = value.map(str::to_owned).map_err(str::to_owned);
+                        ((PathBuf::from(path), *part), value)
+                    })
+                    .collect(),
+            )
+        };
+
+        let before = snapshot(&[
+            ("a.txt", Part::One, Ok("1")),
+            ("a.txt", Part::Two, Ok("2")),
+            ("b.txt", Part::One, Ok("3")),
+        ]);
+        let after = snapshot(&[
+            ("a.txt", Part::One, Ok("1")),
+            ("a.txt", Part::Two, Err("Invalid number!")),
+            ("c.txt", Part::One, Ok("4")),
+        ]);
+
+        assert_eq!(
+            Diff(&before, &after).to_string(),
+            "  a.txt part one: 1\n\
+             ~ a.txt part two: error: Invalid number! (was 2)\n\
+             + c.txt part one: 4\n\
+             - b.txt part one: 3\n"
+        );
+    }
+
+    #[test]
+    fn polls_for_changes() -> std::io::Result<()> {
+        let path = std::env::temp_dir().join(format!("aoc-watch-{}.txt", std::process::id()));
+        let mut poller = Poller::new(vec![path.clone()]);
+
+        assert!(poller.poll().is_empty());
+
+        fs::write(&path, "1\n")?;
+        assert_eq!(poller.poll(), [path.as_path()]);
+        assert!(poller.poll().is_empty());
+
+        fs::write(&path, "12\n")?;
+        assert_eq!(poller.poll(), [path.as_path()]);
+
+        fs::remove_file(&path)?;
+        assert_eq!(poller.poll(), [path.as_path()]);
+        Ok(())
+    }
+}